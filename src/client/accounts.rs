@@ -1,7 +1,11 @@
-use aptos_types::account_address::AccountAddress;
+use aptos_types::{account_address::AccountAddress, account_config};
 use serde::{de::DeserializeOwned, Deserialize};
 
-use crate::types::U64;
+use super::with_ledger_version;
+use crate::{
+    type_tag::{type_string, IntoTypeTag},
+    types::U64,
+};
 
 impl super::Client {
     /// GET /accounts/{address}
@@ -32,8 +36,9 @@ impl super::Client {
     pub fn account_resource<T: DeserializeOwned>(
         &self,
         account_address: AccountAddress,
-        resource_type: &str,
+        resource_type: impl IntoTypeTag,
     ) -> Result<AccountResource<T>, anyhow::Error> {
+        let resource_type = type_string(resource_type)?;
         #[cfg(not(target_arch = "wasm32"))]
         {
             Ok(self
@@ -57,6 +62,66 @@ impl super::Client {
             )?)
         }
     }
+
+    /// GET /accounts/{address}/resource/{resource_type}, Ok(None) if the resource does not exist
+    pub fn account_resource_opt<T: DeserializeOwned>(
+        &self,
+        account_address: AccountAddress,
+        resource_type: impl IntoTypeTag,
+    ) -> Result<Option<AccountResource<T>>, anyhow::Error> {
+        let resource_type = type_string(resource_type)?;
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            match self
+                .inner
+                .get(&format!(
+                    "{}/accounts/{}/resource/{}",
+                    self.base_url, account_address, resource_type
+                ))
+                .call()
+            {
+                Ok(resp) => Ok(Some(resp.into_json::<AccountResource<T>>()?)),
+                Err(ureq::Error::Status(404, _)) => Ok(None),
+                Err(e) => Err(e.into()),
+            }
+        }
+        // TODO: wasm32 target
+        #[cfg(target_arch = "wasm32")]
+        {
+            Ok(Some(
+                self.account_resource::<T>(account_address, resource_type)?,
+            ))
+        }
+    }
+
+    /// GET /accounts/{address} (Accept: application/x-bcs)
+    pub fn account_bcs(
+        &self,
+        account_address: AccountAddress,
+        ledger_version: Option<U64>,
+    ) -> Result<account_config::AccountResource, anyhow::Error> {
+        self.get_bcs::<account_config::AccountResource>(&with_ledger_version(
+            format!("{}/accounts/{}", self.base_url, account_address),
+            ledger_version,
+        ))
+    }
+
+    /// GET /accounts/{address}/resource/{resource_type} (Accept: application/x-bcs)
+    pub fn account_resource_bcs<T: DeserializeOwned>(
+        &self,
+        account_address: AccountAddress,
+        resource_type: impl IntoTypeTag,
+        ledger_version: Option<U64>,
+    ) -> Result<T, anyhow::Error> {
+        let resource_type = type_string(resource_type)?;
+        self.get_bcs::<T>(&with_ledger_version(
+            format!(
+                "{}/accounts/{}/resource/{}",
+                self.base_url, account_address, resource_type
+            ),
+            ledger_version,
+        ))
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -1,53 +1,82 @@
-use aptos_types::account_address::AccountAddress;
+use aptos_types::{account_address::AccountAddress, contract_event::EventWithVersion};
 use serde::{de::DeserializeOwned, Deserialize};
 
-use crate::types::U64;
+use crate::{
+    type_tag::{type_string, IntoTypeTag},
+    types::U64,
+};
 
 impl super::Client {
     /// GET /accounts/{address}/events/{event_handle}/{field_name}
     pub fn events_by_event_handle<T: DeserializeOwned>(
         &self,
         account_address: AccountAddress,
-        event_handle: &str,
+        event_handle: impl IntoTypeTag,
         field_name: &str,
-        _limit: Option<u64>,
-        _start: Option<U64>,
+        limit: Option<u64>,
+        start: Option<U64>,
     ) -> Result<Vec<Event<T>>, anyhow::Error> {
+        let url = self.events_url(account_address, event_handle, field_name, limit, start)?;
         #[cfg(not(target_arch = "wasm32"))]
         {
-            Ok(self
-                .inner
-                .get(&format!(
-                    "{}/accounts/{}/events/{}/{}",
-                    self.base_url,
-                    account_address.to_hex_literal(),
-                    event_handle,
-                    field_name
-                ))
-                .call()?
-                .into_json::<Vec<Event<T>>>()?)
+            Ok(self.inner.get(&url).call()?.into_json::<Vec<Event<T>>>()?)
         }
         #[cfg(target_arch = "wasm32")]
         {
-            Ok(self.fetch::<Vec<Event<T>>>(
-                &format!(
-                    "{}/accounts/{}/events/{}/{}",
-                    self.base_url,
-                    account_address.to_hex_literal(),
-                    event_handle,
-                    field_name
-                ),
-                "GET",
-                None,
-            )?)
+            Ok(self.fetch::<Vec<Event<T>>>(&url, "GET", None)?)
         }
     }
+
+    /// GET /accounts/{address}/events/{event_handle}/{field_name} (Accept: application/x-bcs)
+    pub fn events_by_event_handle_bcs(
+        &self,
+        account_address: AccountAddress,
+        event_handle: impl IntoTypeTag,
+        field_name: &str,
+        limit: Option<u64>,
+        start: Option<U64>,
+    ) -> Result<Vec<EventWithVersion>, anyhow::Error> {
+        let url = self.events_url(account_address, event_handle, field_name, limit, start)?;
+        self.get_bcs::<Vec<EventWithVersion>>(&url)
+    }
+
+    // 事件查询地址, start 与 limit 用于分页
+    fn events_url(
+        &self,
+        account_address: AccountAddress,
+        event_handle: impl IntoTypeTag,
+        field_name: &str,
+        limit: Option<u64>,
+        start: Option<U64>,
+    ) -> Result<String, anyhow::Error> {
+        let event_handle = type_string(event_handle)?;
+        let mut query = vec![];
+        if let Some(start) = start {
+            query.push(format!("start={}", start));
+        }
+        if let Some(limit) = limit {
+            query.push(format!("limit={}", limit));
+        }
+        let url = format!(
+            "{}/accounts/{}/events/{}/{}",
+            self.base_url,
+            account_address.to_hex_literal(),
+            event_handle,
+            field_name
+        );
+        Ok(if query.is_empty() {
+            url
+        } else {
+            format!("{}?{}", url, query.join("&"))
+        })
+    }
 }
 
 #[derive(Debug, Deserialize)]
 pub struct Event<T> {
     #[serde(rename = "type")]
     pub event_type: String,
+    pub version: U64,
     pub sequence_number: U64,
     pub data: T,
 }
@@ -2,22 +2,39 @@ mod accounts;
 mod blocks;
 mod events;
 mod general;
+mod modules;
 mod tables;
 mod transactions;
+mod view;
 
 pub use accounts::*;
 pub use blocks::*;
 pub use events::*;
 pub use general::*;
+pub use modules::*;
 pub use tables::*;
 pub use transactions::*;
+pub use view::*;
 
-#[cfg(target_arch = "wasm32")]
 use serde::de::DeserializeOwned;
+#[cfg(not(target_arch = "wasm32"))]
+use std::io::Read;
 use std::time::Duration;
 #[cfg(target_arch = "wasm32")]
 use wasm_bindgen::{prelude::*, JsCast};
 
+use crate::types::U64;
+
+const BCS_CONTENT_TYPE: &str = "application/x-bcs";
+
+// 附加 ledger_version 查询参数
+fn with_ledger_version(url: String, ledger_version: Option<U64>) -> String {
+    match ledger_version {
+        Some(version) => format!("{}?ledger_version={}", url, version),
+        None => url,
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Client {
     base_url: String,
@@ -40,6 +57,56 @@ impl Client {
             inner: web_sys::window().unwrap(),
         }
     }
+
+    // 以 BCS 格式请求 (Accept: application/x-bcs) 并解码
+    fn get_bcs<T: DeserializeOwned>(&self, url: &str) -> Result<T, anyhow::Error> {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let mut bytes = vec![];
+            self.inner
+                .get(url)
+                .set("Accept", BCS_CONTENT_TYPE)
+                .call()?
+                .into_reader()
+                .read_to_end(&mut bytes)?;
+            Ok(bcs::from_bytes::<T>(&bytes)?)
+        }
+        // TODO: wasm32 target
+        #[cfg(target_arch = "wasm32")]
+        {
+            Err(anyhow::anyhow!(
+                "bcs response is not supported on wasm32: {}",
+                url
+            ))
+        }
+    }
+
+    fn post_json_bcs<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        body: serde_json::Value,
+    ) -> Result<T, anyhow::Error> {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let mut bytes = vec![];
+            self.inner
+                .post(url)
+                .set("Accept", BCS_CONTENT_TYPE)
+                .send_json(body)?
+                .into_reader()
+                .read_to_end(&mut bytes)?;
+            Ok(bcs::from_bytes::<T>(&bytes)?)
+        }
+        // TODO: wasm32 target
+        #[cfg(target_arch = "wasm32")]
+        {
+            let _ = body;
+            Err(anyhow::anyhow!(
+                "bcs response is not supported on wasm32: {}",
+                url
+            ))
+        }
+    }
     #[cfg(target_arch = "wasm32")]
     async fn fetch_jsvalue<T: DeserializeOwned>(
         &self,
@@ -0,0 +1,101 @@
+use aptos_types::account_address::AccountAddress;
+use serde::Deserialize;
+
+impl super::Client {
+    /// GET /accounts/{address}/module/{module_name}
+    pub fn account_module(
+        &self,
+        account_address: AccountAddress,
+        module_name: &str,
+    ) -> Result<MoveModuleBytecode, anyhow::Error> {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            Ok(self
+                .inner
+                .get(&format!(
+                    "{}/accounts/{}/module/{}",
+                    self.base_url, account_address, module_name
+                ))
+                .call()?
+                .into_json::<MoveModuleBytecode>()?)
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            Ok(self.fetch::<MoveModuleBytecode>(
+                &format!(
+                    "{}/accounts/{}/module/{}",
+                    self.base_url, account_address, module_name
+                ),
+                "GET",
+                None,
+            )?)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MoveModuleBytecode {
+    pub bytecode: String,
+    pub abi: Option<MoveModule>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MoveModule {
+    pub address: AccountAddress,
+    pub name: String,
+    pub friends: Vec<String>,
+    pub exposed_functions: Vec<MoveFunction>,
+    pub structs: Vec<MoveStruct>,
+}
+
+impl MoveModule {
+    pub fn function(&self, name: &str) -> Option<&MoveFunction> {
+        self.exposed_functions.iter().find(|f| f.name == name)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MoveFunction {
+    pub name: String,
+    pub visibility: String,
+    pub is_entry: bool,
+    #[serde(default)]
+    pub is_view: bool,
+    pub generic_type_params: Vec<MoveGenericTypeParam>,
+    pub params: Vec<String>,
+    #[serde(rename = "return")]
+    pub return_types: Vec<String>,
+}
+
+impl MoveFunction {
+    // 去掉开头的 signer 参数, 剩余为需要传入的参数
+    pub fn non_signer_params(&self) -> &[String] {
+        let signers = self
+            .params
+            .iter()
+            .take_while(|p| p.as_str() == "signer" || p.as_str() == "&signer")
+            .count();
+        &self.params[signers..]
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MoveGenericTypeParam {
+    pub constraints: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MoveStruct {
+    pub name: String,
+    pub is_native: bool,
+    pub abilities: Vec<String>,
+    pub generic_type_params: Vec<MoveGenericTypeParam>,
+    pub fields: Vec<MoveStructField>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MoveStructField {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub field_type: String,
+}
@@ -1,22 +1,36 @@
-use serde::de::DeserializeOwned;
+use anyhow::Context;
+use aptos_types::{account_address::AccountAddress, state_store::table::TableHandle};
+use move_core_types::language_storage::TypeTag;
+use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize};
+use std::{fmt, marker::PhantomData};
 
-use crate::types::U64;
+use super::{with_ledger_version, Client};
+use crate::{
+    type_tag::{type_string, IntoTypeTag},
+    types::{MoveType, U64},
+};
 
 impl super::Client {
     /// POST /tables/{table_handle}/item
     pub fn table_item<T: DeserializeOwned>(
         &self,
         table_handle: &str,
-        key_type: &str,
-        value_type: &str,
+        key_type: impl IntoTypeTag,
+        value_type: impl IntoTypeTag,
         key: serde_json::Value,
-        _ledger_version: Option<U64>,
+        ledger_version: Option<U64>,
     ) -> Result<T, anyhow::Error> {
+        let key_type = type_string(key_type)?;
+        let value_type = type_string(value_type)?;
+        let url = with_ledger_version(
+            format!("{}/tables/{}/item", self.base_url, table_handle),
+            ledger_version,
+        );
         #[cfg(not(target_arch = "wasm32"))]
         {
             Ok(self
                 .inner
-                .post(&format!("{}/tables/{}/item", self.base_url, table_handle))
+                .post(&url)
                 .send_json(serde_json::json!({
                     "key_type": key_type,
                     "value_type": value_type,
@@ -27,7 +41,7 @@ impl super::Client {
         #[cfg(target_arch = "wasm32")]
         {
             Ok(self.fetch::<T>(
-                &format!("{}/tables/{}/item", self.base_url, table_handle),
+                &url,
                 "POST",
                 Some(
                     &serde_wasm_bindgen::to_value(&serde_json::json!({
@@ -40,4 +54,238 @@ impl super::Client {
             )?)
         }
     }
+
+    /// POST /tables/{table_handle}/item, Ok(None) if the item does not exist
+    pub fn table_item_opt<T: DeserializeOwned>(
+        &self,
+        table_handle: &str,
+        key_type: impl IntoTypeTag,
+        value_type: impl IntoTypeTag,
+        key: serde_json::Value,
+        ledger_version: Option<U64>,
+    ) -> Result<Option<T>, anyhow::Error> {
+        let key_type = type_string(key_type)?;
+        let value_type = type_string(value_type)?;
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            match self
+                .inner
+                .post(&with_ledger_version(
+                    format!("{}/tables/{}/item", self.base_url, table_handle),
+                    ledger_version,
+                ))
+                .send_json(serde_json::json!({
+                    "key_type": key_type,
+                    "value_type": value_type,
+                    "key": key,
+                })) {
+                Ok(resp) => Ok(Some(resp.into_json::<T>()?)),
+                Err(ureq::Error::Status(404, _)) => Ok(None),
+                Err(e) => Err(e.into()),
+            }
+        }
+        // TODO: wasm32 target
+        #[cfg(target_arch = "wasm32")]
+        {
+            Ok(Some(self.table_item::<T>(
+                table_handle,
+                key_type,
+                value_type,
+                key,
+                ledger_version,
+            )?))
+        }
+    }
+
+    /// POST /tables/{table_handle}/raw_item
+    pub fn table_item_bcs<K: Serialize, V: DeserializeOwned>(
+        &self,
+        table_handle: TableHandle,
+        key: &K,
+        ledger_version: Option<U64>,
+    ) -> Result<V, anyhow::Error> {
+        self.post_json_bcs::<V>(
+            &with_ledger_version(
+                format!(
+                    "{}/tables/{}/raw_item",
+                    self.base_url,
+                    table_handle.0.to_hex_literal()
+                ),
+                ledger_version,
+            ),
+            serde_json::json!({
+                "key": format!("0x{}", hex::encode(bcs::to_bytes(key)?)),
+            }),
+        )
+        .with_context(|| format!("table item in table {}", table_handle.0.to_hex_literal()))
+    }
+}
+
+/// A Move `0x1::table::Table<K, V>` field of a resource
+///
+/// Deserialized from the table handle, key and value types come from `K` and `V`
+pub struct Table<K, V> {
+    handle: TableHandle,
+    phantom: PhantomData<fn() -> (K, V)>,
+}
+
+impl<K, V> Table<K, V> {
+    pub fn new(handle: TableHandle) -> Self {
+        Self {
+            handle,
+            phantom: PhantomData,
+        }
+    }
+
+    pub fn handle(&self) -> TableHandle {
+        self.handle
+    }
+}
+
+impl<K: MoveType + Serialize, V: MoveType + DeserializeOwned> Table<K, V> {
+    pub fn key_type() -> TypeTag {
+        K::type_tag()
+    }
+
+    pub fn value_type() -> TypeTag {
+        V::type_tag()
+    }
+
+    pub fn get(&self, client: &Client, key: &K) -> Result<V, anyhow::Error> {
+        self.get_item(client, key, None)
+    }
+
+    pub fn get_at_version(
+        &self,
+        client: &Client,
+        key: &K,
+        ledger_version: u64,
+    ) -> Result<V, anyhow::Error> {
+        self.get_item(client, key, Some(U64(ledger_version)))
+    }
+
+    // 不存在时返回 None
+    pub fn try_get(&self, client: &Client, key: &K) -> Result<Option<V>, anyhow::Error> {
+        client.table_item_opt::<V>(
+            &self.handle.0.to_hex_literal(),
+            Self::key_type(),
+            Self::value_type(),
+            serde_json::to_value(key)?,
+            None,
+        )
+    }
+
+    // 以 BCS 读取, V 的字段须与 Move 结构完全一致
+    pub fn get_bcs(&self, client: &Client, key: &K) -> Result<V, anyhow::Error> {
+        client.table_item_bcs::<K, V>(self.handle, key, None)
+    }
+
+    fn get_item(
+        &self,
+        client: &Client,
+        key: &K,
+        ledger_version: Option<U64>,
+    ) -> Result<V, anyhow::Error> {
+        client.table_item::<V>(
+            &self.handle.0.to_hex_literal(),
+            Self::key_type(),
+            Self::value_type(),
+            serde_json::to_value(key)?,
+            ledger_version,
+        )
+    }
+}
+
+impl<K, V> Clone for Table<K, V> {
+    fn clone(&self) -> Self {
+        Self::new(self.handle)
+    }
+}
+
+impl<K, V> Copy for Table<K, V> {}
+
+impl<K, V> fmt::Debug for Table<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Table")
+            .field("handle", &self.handle.0)
+            .finish()
+    }
+}
+
+impl<'de, K, V> Deserialize<'de> for Table<K, V> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        // JSON 为 {"handle": "0x..."}, BCS 为 struct Table { handle: address }
+        #[derive(Deserialize)]
+        struct RawTable {
+            handle: AccountAddress,
+        }
+        let raw = RawTable::deserialize(deserializer)?;
+        Ok(Self::new(TableHandle(raw.handle)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        token::{Collections, Token, TokenData, TokenDataId, TokenId},
+        type_tag::format_type_tag,
+    };
+
+    fn address(hex: &str) -> AccountAddress {
+        AccountAddress::from_hex_literal(hex).unwrap()
+    }
+
+    #[test]
+    fn table_from_resource_json() {
+        // 节点返回的 0x3::token::Collections, 其余字段被忽略
+        let collections = serde_json::from_value::<Collections>(serde_json::json!({
+            "collection_data": {"handle": "0xc0"},
+            "create_collection_events": {
+                "counter": "1",
+                "guid": {"id": {"addr": "0xcafe", "creation_num": "3"}}
+            },
+            "token_data": {"handle": "0xd0"}
+        }))
+        .unwrap();
+        assert_eq!(
+            collections.collection_data.handle(),
+            TableHandle(address("0xc0"))
+        );
+        assert_eq!(
+            collections.token_data.handle(),
+            TableHandle(address("0xd0"))
+        );
+    }
+
+    #[test]
+    fn table_from_bcs() {
+        let handle = address("0xabcd");
+        let table =
+            bcs::from_bytes::<Table<TokenId, Token>>(&bcs::to_bytes(&handle).unwrap()).unwrap();
+        assert_eq!(table.handle(), TableHandle(handle));
+    }
+
+    #[test]
+    fn table_types() {
+        assert_eq!(
+            format_type_tag(&Table::<TokenDataId, TokenData>::key_type()),
+            "0x3::token::TokenDataId"
+        );
+        assert_eq!(
+            format_type_tag(&Table::<TokenDataId, TokenData>::value_type()),
+            "0x3::token::TokenData"
+        );
+        assert_eq!(
+            format_type_tag(&Table::<String, Vec<u64>>::key_type()),
+            "0x1::string::String"
+        );
+        assert_eq!(
+            format_type_tag(&Table::<String, Vec<u64>>::value_type()),
+            "vector<u64>"
+        );
+    }
 }
@@ -1,4 +1,14 @@
-use aptos_types::transaction::{RawTransaction, SignedTransaction, TransactionPayload};
+use aptos_types::{
+    account_address::AccountAddress,
+    transaction::{
+        EntryFunction, RawTransaction, SignedTransaction, TransactionOnChainData,
+        TransactionPayload,
+    },
+};
+use move_core_types::{
+    identifier::Identifier,
+    language_storage::{ModuleId, TypeTag},
+};
 use serde::Deserialize;
 use std::{
     thread,
@@ -7,7 +17,12 @@ use std::{
 #[cfg(target_arch = "wasm32")]
 use wasm_bindgen::JsValue;
 
-use crate::types::{LocalAccount, U64};
+use crate::{
+    entry_function::EntryFunctionCall,
+    multi_agent::MultiAgentTransaction,
+    script::ScriptCall,
+    types::{LocalAccount, U64},
+};
 
 impl super::Client {
     /// GET /transactions/by_hash/{txn_hash}
@@ -33,6 +48,40 @@ impl super::Client {
         }
     }
 
+    /// GET /transactions/by_version/{txn_version}
+    pub fn transaction_by_version(&self, txn_version: u64) -> Result<Transaction, anyhow::Error> {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            Ok(self
+                .inner
+                .get(&format!(
+                    "{}/transactions/by_version/{}",
+                    self.base_url, txn_version
+                ))
+                .call()?
+                .into_json::<Transaction>()?)
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            Ok(self.fetch::<Transaction>(
+                &format!("{}/transactions/by_version/{}", self.base_url, txn_version),
+                "GET",
+                None,
+            )?)
+        }
+    }
+
+    /// GET /transactions/by_hash/{txn_hash} (Accept: application/x-bcs)
+    pub fn transaction_by_hash_bcs(
+        &self,
+        txn_hash: &str,
+    ) -> Result<TransactionData, anyhow::Error> {
+        self.get_bcs::<TransactionData>(&format!(
+            "{}/transactions/by_hash/{}",
+            self.base_url, txn_hash
+        ))
+    }
+
     /// POST /transactions
     pub fn submit_bcs_transaction(
         &self,
@@ -63,31 +112,127 @@ impl super::Client {
         }
     }
 
+    // 构建未签名交易, sequence number 与 chain id 从链上读取
+    pub fn build_raw_transaction(
+        &self,
+        sender: AccountAddress,
+        payload: TransactionPayload,
+    ) -> Result<RawTransaction, anyhow::Error> {
+        Ok(RawTransaction::new(
+            sender,
+            self.account(sender, None)?.sequence_number.0,
+            payload,
+            100_000,
+            100,
+            SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() + 600,
+            self.ledger_info()?.chain_id,
+        ))
+    }
+
     // single signer sign transaction
     pub fn create_single_signer_bcs_transaction(
         &self,
         sender: LocalAccount,
         payload: TransactionPayload,
     ) -> SignedTransaction {
-        let txn = RawTransaction::new(
-            sender.address(),
-            self.account(sender.address(), None)
-                .unwrap()
-                .sequence_number
-                .0,
-            payload,
-            100_000,
-            100,
-            SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .unwrap()
-                .as_secs()
-                + 600,
-            self.ledger_info().unwrap().chain_id,
-        );
+        let txn = self
+            .build_raw_transaction(sender.address(), payload)
+            .unwrap();
         sender.sign_transaction(txn)
     }
 
+    // 构造 multi-agent 交易, 由 sender 与各 secondary signer 分别签名
+    pub fn build_multi_agent_transaction(
+        &self,
+        sender: AccountAddress,
+        secondary_signers: Vec<AccountAddress>,
+        payload: TransactionPayload,
+    ) -> Result<MultiAgentTransaction, anyhow::Error> {
+        let raw_transaction = self.build_raw_transaction(sender, payload)?;
+        Ok(MultiAgentTransaction::new(
+            raw_transaction,
+            secondary_signers,
+        ))
+    }
+
+    // 构造由 fee_payer 支付 gas 的交易, sender 无需持有 APT
+    pub fn build_fee_payer_transaction(
+        &self,
+        sender: AccountAddress,
+        secondary_signers: Vec<AccountAddress>,
+        fee_payer: AccountAddress,
+        payload: TransactionPayload,
+    ) -> Result<MultiAgentTransaction, anyhow::Error> {
+        let raw_transaction = self.build_raw_transaction(sender, payload)?;
+        Ok(MultiAgentTransaction::with_fee_payer(
+            raw_transaction,
+            secondary_signers,
+            fee_payer,
+        ))
+    }
+
+    // 提交签名完成的 multi-agent 或 fee payer 交易, 返回交易 hash
+    pub fn submit_multi_agent_transaction(
+        &self,
+        transaction: MultiAgentTransaction,
+    ) -> Result<String, anyhow::Error> {
+        self.submit_bcs_transaction(transaction.into_signed_transaction()?)
+    }
+
+    // 签名并提交 entry function 交易, 返回交易 hash
+    pub fn submit_entry_function(
+        &self,
+        sender: LocalAccount,
+        module: ModuleId,
+        function: &str,
+        ty_args: Vec<TypeTag>,
+        args: Vec<Vec<u8>>,
+    ) -> Result<String, anyhow::Error> {
+        let payload = TransactionPayload::EntryFunction(EntryFunction::new(
+            module,
+            Identifier::new(function)?,
+            ty_args,
+            args,
+        ));
+        let signed_transaction = self.create_single_signer_bcs_transaction(sender, payload);
+        self.submit_bcs_transaction(signed_transaction)
+    }
+
+    // 按模块 ABI 检查参数后签名并提交, 返回交易 hash
+    pub fn submit_entry_function_call(
+        &self,
+        sender: LocalAccount,
+        call: &EntryFunctionCall,
+    ) -> Result<String, anyhow::Error> {
+        let payload = self.checked_entry_function_payload(call)?;
+        let signed_transaction = self.create_single_signer_bcs_transaction(sender, payload);
+        self.submit_bcs_transaction(signed_transaction)
+    }
+
+    // 签名并提交 script 交易, 返回交易 hash
+    pub fn submit_script(
+        &self,
+        sender: LocalAccount,
+        script: &ScriptCall,
+    ) -> Result<String, anyhow::Error> {
+        let signed_transaction =
+            self.create_single_signer_bcs_transaction(sender, script.payload());
+        self.submit_bcs_transaction(signed_transaction)
+    }
+
+    // 读取链上模块 ABI 检查参数, 不匹配时在本地报错
+    pub fn checked_entry_function_payload(
+        &self,
+        call: &EntryFunctionCall,
+    ) -> Result<TransactionPayload, anyhow::Error> {
+        let abi = self
+            .account_module(*call.module.address(), call.module.name().as_str())?
+            .abi
+            .ok_or_else(|| anyhow::anyhow!("module {} has no abi", call.module))?;
+        call.check(&abi)?;
+        call.payload()
+    }
+
     // 等待交易完成
     pub fn wait_for_transaction(&self, txn_hash: &str) -> () {
         let mut count = 0;
@@ -102,6 +247,26 @@ impl super::Client {
         ()
     }
 
+    // 等待交易上链并执行成功, 执行失败 (success 为 false) 时返回包含 vm_status 的错误
+    pub fn wait_for_committed_transaction(
+        &self,
+        txn_hash: &str,
+    ) -> Result<Transaction, anyhow::Error> {
+        self.wait_for_transaction(txn_hash);
+        let txn = self.transaction_by_hash(txn_hash)?;
+        if txn.transaction_type == "pending_transaction" {
+            return Err(anyhow::anyhow!("transaction {} is still pending", txn_hash));
+        }
+        if txn.success == Some(false) {
+            return Err(anyhow::anyhow!(
+                "transaction {} failed: {}",
+                txn_hash,
+                txn.vm_status.as_deref().unwrap_or("unknown vm status")
+            ));
+        }
+        Ok(txn)
+    }
+
     // 以下私有方法
     fn transaction_pending(&self, txn_hash: &str) -> bool {
         #[cfg(not(target_arch = "wasm32"))]
@@ -142,10 +307,117 @@ impl super::Client {
 pub struct Transaction {
     #[serde(rename = "type")]
     pub transaction_type: String,
+    #[serde(default)]
     pub sequence_number: U64,
+    #[serde(default)]
+    pub hash: Option<String>,
+    #[serde(default)]
+    pub version: Option<U64>,
+    #[serde(default)]
+    pub success: Option<bool>,
+    #[serde(default)]
+    pub vm_status: Option<String>,
+    #[serde(default)]
+    pub timestamp: Option<U64>,
+    #[serde(default)]
+    pub events: Vec<TransactionEvent>,
+}
+
+/// An event emitted by a committed transaction
+#[derive(Debug, Deserialize)]
+pub struct TransactionEvent {
+    #[serde(rename = "type")]
+    pub event_type: String,
+    pub data: serde_json::Value,
+}
+
+/// BCS response of the transaction endpoints, variants in the same order as the node.
+#[derive(Debug, Deserialize)]
+pub enum TransactionData {
+    OnChain(TransactionOnChainData),
+    Pending(Box<SignedTransaction>),
 }
 
 #[derive(Deserialize)]
 pub struct SubmitTransaction {
     pub hash: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_crypto::{ed25519::Ed25519PrivateKey, HashValue};
+    use aptos_types::{
+        chain_id::ChainId,
+        transaction::{ExecutionStatus, Script, Transaction, TransactionInfo},
+        write_set::WriteSet,
+    };
+    use serde::Serialize;
+
+    // 与节点返回的 BCS 枚举相同的定义
+    #[derive(Serialize)]
+    enum NodeTransactionData {
+        OnChain(TransactionOnChainData),
+        Pending(Box<SignedTransaction>),
+    }
+
+    fn signed_transaction() -> SignedTransaction {
+        let account = LocalAccount::new(
+            AccountAddress::ONE,
+            Ed25519PrivateKey::try_from(&[1u8; 32][..]).unwrap(),
+            0,
+        );
+        account.sign_transaction(RawTransaction::new(
+            AccountAddress::ONE,
+            0,
+            TransactionPayload::Script(Script::new(vec![], vec![], vec![])),
+            1_000,
+            100,
+            u64::MAX,
+            ChainId::test(),
+        ))
+    }
+
+    #[test]
+    fn pending_transaction_bcs() {
+        let bytes = bcs::to_bytes(&NodeTransactionData::Pending(
+            Box::new(signed_transaction()),
+        ))
+        .unwrap();
+        match bcs::from_bytes::<TransactionData>(&bytes).unwrap() {
+            TransactionData::Pending(txn) => assert_eq!(*txn, signed_transaction()),
+            other => panic!("expected a pending transaction, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn on_chain_transaction_bcs() {
+        let data = TransactionOnChainData {
+            version: 42,
+            transaction: Transaction::UserTransaction(signed_transaction()),
+            info: TransactionInfo::new(
+                HashValue::zero(),
+                HashValue::zero(),
+                HashValue::zero(),
+                None,
+                7,
+                ExecutionStatus::Success,
+            ),
+            events: vec![],
+            accumulator_root_hash: HashValue::zero(),
+            changes: WriteSet::default(),
+        };
+        let bytes = bcs::to_bytes(&NodeTransactionData::OnChain(data)).unwrap();
+        match bcs::from_bytes::<TransactionData>(&bytes).unwrap() {
+            TransactionData::OnChain(txn) => {
+                assert_eq!(txn.version, 42);
+                assert_eq!(txn.info.gas_used(), 7);
+                assert_eq!(
+                    txn.transaction,
+                    Transaction::UserTransaction(signed_transaction())
+                );
+            }
+            other => panic!("expected an on-chain transaction, got {:?}", other),
+        }
+    }
+}
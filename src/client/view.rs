@@ -0,0 +1,37 @@
+use crate::types::U64;
+
+use super::with_ledger_version;
+
+impl super::Client {
+    /// POST /view
+    pub fn view(
+        &self,
+        function: &str,
+        type_arguments: Vec<String>,
+        arguments: Vec<serde_json::Value>,
+        ledger_version: Option<U64>,
+    ) -> Result<Vec<serde_json::Value>, anyhow::Error> {
+        let url = with_ledger_version(format!("{}/view", self.base_url), ledger_version);
+        let body = serde_json::json!({
+            "function": function,
+            "type_arguments": type_arguments,
+            "arguments": arguments,
+        });
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            Ok(self
+                .inner
+                .post(&url)
+                .send_json(body)?
+                .into_json::<Vec<serde_json::Value>>()?)
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            Ok(self.fetch::<Vec<serde_json::Value>>(
+                &url,
+                "POST",
+                Some(&serde_wasm_bindgen::to_value(&body).unwrap()),
+            )?)
+        }
+    }
+}
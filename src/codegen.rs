@@ -0,0 +1,665 @@
+use aptos_types::account_address::AccountAddress;
+use move_binary_format::{
+    access::ModuleAccess,
+    file_format::{
+        Ability, AbilitySet, SignatureToken, StructFieldInformation, StructHandleIndex, Visibility,
+    },
+    CompiledModule,
+};
+use std::{collections::HashSet, fmt::Write, fs, path::Path};
+
+use crate::{
+    client::{
+        MoveFunction, MoveGenericTypeParam, MoveModule, MoveModuleBytecode, MoveStruct,
+        MoveStructField,
+    },
+    module::read_bytecode_modules,
+};
+
+/// Generate Rust bindings for Move modules, one `mod` per module
+///
+/// Each module gets a client struct on top of `ModuleClient` with a method per entry and view
+/// function, and a serde struct per Move struct for reading resources. Meant to be called from
+/// a `build.rs`, writing the result to `OUT_DIR` and `include!`-ing it.
+///
+/// Functions named `new` or `with_address` get a `move_` prefix so they don't clash with the
+/// constructors of the client. Entry functions taking more than one `&signer` return the
+/// `MultiAgentTransaction` to be signed by every signer instead of submitting it.
+pub fn generate_bindings(modules: &[MoveModule]) -> String {
+    let known_structs: HashSet<(AccountAddress, String, String)> = modules
+        .iter()
+        .flat_map(|m| {
+            m.structs
+                .iter()
+                .filter(|s| s.generic_type_params.is_empty() && !s.is_native)
+                .map(move |s| (m.address, m.name.clone(), s.name.clone()))
+        })
+        .collect();
+    let mut out = String::new();
+    out.push_str("// @generated by aptos_client::generate_bindings, do not edit\n");
+    for module in modules {
+        Generator {
+            module,
+            known_structs: &known_structs,
+        }
+        .module(&mut out);
+    }
+    out
+}
+
+/// Generate bindings from an ABI JSON file
+///
+/// The file may hold a module ABI, the response of GET /accounts/{address}/module/{name},
+/// or an array of either.
+pub fn generate_bindings_from_abi_file<P: AsRef<Path>>(path: P) -> Result<String, anyhow::Error> {
+    let value: serde_json::Value = serde_json::from_slice(&fs::read(path.as_ref())?)?;
+    let values = match value {
+        serde_json::Value::Array(values) => values,
+        value => vec![value],
+    };
+    let mut modules = vec![];
+    for value in values {
+        if value.get("abi").is_some() {
+            let bytecode: MoveModuleBytecode = serde_json::from_value(value)?;
+            modules.push(bytecode.abi.ok_or_else(|| {
+                anyhow::anyhow!("module in {} has no abi", path.as_ref().display())
+            })?);
+        } else {
+            modules.push(serde_json::from_value::<MoveModule>(value)?);
+        }
+    }
+    Ok(generate_bindings(&modules))
+}
+
+/// Generate bindings from a compiled package, `build/<package>` of the Move compiler output
+///
+/// View functions are only marked in the ABI JSON, so none are generated for compiled modules.
+pub fn generate_bindings_from_package<P: AsRef<Path>>(
+    package_dir: P,
+) -> Result<String, anyhow::Error> {
+    let modules: Vec<MoveModule> = read_bytecode_modules(package_dir.as_ref())?
+        .iter()
+        .map(|(_, module)| module_abi(module))
+        .collect();
+    Ok(generate_bindings(&modules))
+}
+
+struct Generator<'a> {
+    module: &'a MoveModule,
+    known_structs: &'a HashSet<(AccountAddress, String, String)>,
+}
+
+impl<'a> Generator<'a> {
+    fn module(&self, out: &mut String) {
+        let module = self.module;
+        let client = format!("{}Module", camel_case(&module.name));
+        let _ = writeln!(out, "\npub mod {} {{", rust_ident(&module.name));
+        out.push_str("    #![allow(dead_code, unused_imports, clippy::all)]\n");
+        out.push_str("    use ::aptos_client::aptos_types::account_address::AccountAddress;\n");
+        out.push_str("    use ::aptos_client::move_core_types::language_storage::TypeTag;\n");
+        out.push_str("    use ::aptos_client::types::LocalAccount;\n");
+        out.push_str("    use ::aptos_client::{EntryFunctionCall, MoveArgument, ModuleClient, MultiAgentTransaction};\n\n");
+        let _ = writeln!(
+            out,
+            "    pub const ADDRESS: &str = \"{}\";",
+            module.address.to_hex_literal()
+        );
+        let _ = writeln!(out, "    pub const NAME: &str = \"{}\";\n", module.name);
+
+        let _ = writeln!(out, "    pub struct {} {{", client);
+        out.push_str("        pub address: AccountAddress,\n");
+        out.push_str("        pub module: ModuleClient,\n");
+        out.push_str("    }\n\n");
+        let _ = writeln!(out, "    impl {} {{", client);
+        out.push_str("        pub fn new(rest_url: &str) -> Self {\n");
+        out.push_str(
+            "            Self::with_address(rest_url, AccountAddress::from_hex_literal(ADDRESS).unwrap())\n",
+        );
+        out.push_str("        }\n\n");
+        out.push_str("        // 模块发布在其他地址时使用\n");
+        out.push_str(
+            "        pub fn with_address(rest_url: &str, address: AccountAddress) -> Self {\n",
+        );
+        out.push_str("            Self {\n");
+        out.push_str("                address,\n");
+        out.push_str("                module: ModuleClient::new(rest_url, address),\n");
+        out.push_str("            }\n");
+        out.push_str("        }\n");
+        let function_names: HashSet<&str> = module
+            .exposed_functions
+            .iter()
+            .map(|function| function.name.as_str())
+            .collect();
+        for function in &module.exposed_functions {
+            let method = method_name(&function.name, &function_names);
+            if function.is_entry && signer_count(function) > 1 {
+                self.multi_agent_function(out, function, &method);
+            } else if function.is_entry {
+                self.entry_function(out, function, &method);
+            } else if function.is_view {
+                self.view_function(out, function, &method);
+            }
+        }
+        out.push_str("    }\n");
+
+        for move_struct in &module.structs {
+            if !move_struct.is_native {
+                self.move_struct(out, move_struct);
+            }
+        }
+        out.push_str("}\n");
+    }
+
+    fn entry_function(&self, out: &mut String, function: &MoveFunction, method: &str) {
+        let params = function.non_signer_params();
+        let _ = write!(
+            out,
+            "\n        pub fn {}(&self, account: LocalAccount",
+            method
+        );
+        self.params(out, function, params);
+        out.push_str(") -> Result<String, ::aptos_client::anyhow::Error> {\n");
+        self.arguments(out, params);
+        out.push_str("            let mut bcs_args = vec![];\n");
+        out.push_str("            for arg in &args {\n");
+        out.push_str("                bcs_args.push(::aptos_client::bcs::to_bytes(arg)?);\n");
+        out.push_str("            }\n");
+        let _ = writeln!(
+            out,
+            "            self.module.entry_function(account, NAME, \"{}\", {}, bcs_args)",
+            function.name,
+            ty_args_expr(function)
+        );
+        out.push_str("        }\n");
+    }
+
+    // 多个 signer 的 entry function 只构造交易, 由各 signer 分别签名后提交
+    fn multi_agent_function(&self, out: &mut String, function: &MoveFunction, method: &str) {
+        let params = function.non_signer_params();
+        let secondary_signers = signer_count(function) - 1;
+        let _ = writeln!(
+            out,
+            "\n        // 需要 sender 与 {} 个 secondary signer 签名, 见 MultiAgentTransaction",
+            secondary_signers
+        );
+        let _ = write!(
+            out,
+            "        pub fn {}(&self, sender: AccountAddress, secondary_signers: Vec<AccountAddress>",
+            method
+        );
+        self.params(out, function, params);
+        out.push_str(") -> Result<MultiAgentTransaction, ::aptos_client::anyhow::Error> {\n");
+        let _ = writeln!(
+            out,
+            "            if secondary_signers.len() != {} {{",
+            secondary_signers
+        );
+        let _ = writeln!(
+            out,
+            "                return Err(::aptos_client::anyhow::anyhow!(\"{} expects {} secondary signers, got {{}}\", secondary_signers.len()));",
+            function.name, secondary_signers
+        );
+        out.push_str("            }\n");
+        self.arguments(out, params);
+        out.push_str("            let module = ::aptos_client::move_core_types::language_storage::ModuleId::new(\n");
+        out.push_str("                self.address,\n");
+        out.push_str("                ::aptos_client::move_core_types::identifier::Identifier::new(NAME)?,\n");
+        out.push_str("            );\n");
+        let _ = writeln!(
+            out,
+            "            let payload = EntryFunctionCall::new(module, \"{}\", {}, args).payload()?;",
+            function.name,
+            ty_args_expr(function)
+        );
+        out.push_str("            self.module\n");
+        out.push_str("                .client\n");
+        out.push_str(
+            "                .build_multi_agent_transaction(sender, secondary_signers, payload)\n",
+        );
+        out.push_str("        }\n");
+    }
+
+    fn view_function(&self, out: &mut String, function: &MoveFunction, method: &str) {
+        let params = &function.params[..];
+        let _ = write!(out, "\n        pub fn {}(&self", method);
+        self.params(out, function, params);
+        out.push_str(
+            ") -> Result<Vec<::aptos_client::serde_json::Value>, ::aptos_client::anyhow::Error> {\n",
+        );
+        self.arguments(out, params);
+        out.push_str("            self.module.client.view(\n");
+        let _ = writeln!(
+            out,
+            "                &format!(\"{{}}::{{}}::{}\", self.address.to_hex_literal(), NAME),",
+            function.name
+        );
+        let _ = writeln!(
+            out,
+            "                {}.iter().map(::aptos_client::format_type_tag).collect(),",
+            ty_args_expr(function)
+        );
+        out.push_str("                args.iter().map(|arg| arg.to_json()).collect(),\n");
+        out.push_str("                None,\n");
+        out.push_str("            )\n");
+        out.push_str("        }\n");
+    }
+
+    fn params(&self, out: &mut String, function: &MoveFunction, params: &[String]) {
+        if !function.generic_type_params.is_empty() {
+            out.push_str(", ty_args: Vec<TypeTag>");
+        }
+        for (i, param) in params.iter().enumerate() {
+            let _ = write!(out, ", arg{}: {}", i, argument_type(param));
+        }
+    }
+
+    fn arguments(&self, out: &mut String, params: &[String]) {
+        out.push_str("            let args: Vec<MoveArgument> = vec![");
+        for (i, param) in params.iter().enumerate() {
+            if i > 0 {
+                out.push_str(", ");
+            }
+            out.push_str(&argument_expr(&format!("arg{}", i), param, 0));
+        }
+        out.push_str("];\n");
+    }
+
+    fn move_struct(&self, out: &mut String, move_struct: &MoveStruct) {
+        let _ = writeln!(
+            out,
+            "\n    /// {}::{}::{}",
+            self.module.address.to_hex_literal(),
+            self.module.name,
+            move_struct.name
+        );
+        out.push_str("    #[derive(Debug, Clone, ::aptos_client::serde::Deserialize)]\n");
+        out.push_str("    #[serde(crate = \"::aptos_client::serde\")]\n");
+        let _ = writeln!(out, "    pub struct {} {{", move_struct.name);
+        for field in &move_struct.fields {
+            let name = rust_ident(&field.name);
+            if name != field.name {
+                let _ = writeln!(out, "        #[serde(rename = \"{}\")]", field.name);
+            }
+            let _ = writeln!(
+                out,
+                "        pub {}: {},",
+                name,
+                self.field_type(&field.field_type)
+            );
+        }
+        out.push_str("    }\n");
+    }
+
+    // 资源 JSON 中的字段类型
+    fn field_type(&self, ty: &str) -> String {
+        let (base, type_args) = split_type(ty);
+        match (base, type_args.as_slice()) {
+            ("bool", []) | ("u8", []) | ("u16", []) | ("u32", []) => base.to_string(),
+            ("u64", []) => "::aptos_client::types::U64".to_string(),
+            ("u128", []) => "::aptos_client::types::U128".to_string(),
+            ("u256", []) => "::aptos_client::types::U256".to_string(),
+            ("address", []) => "AccountAddress".to_string(),
+            ("0x1::string::String", []) => "String".to_string(),
+            ("vector", [inner]) if inner == "u8" => "::aptos_client::types::HexBytes".to_string(),
+            ("vector", [inner]) => format!("Vec<{}>", self.field_type(inner)),
+            ("0x1::option::Option", [inner]) => format!(
+                "::aptos_client::types::MoveOption<{}>",
+                self.field_type(inner)
+            ),
+            ("0x1::object::Object", [_]) => "::aptos_client::types::ObjectRef".to_string(),
+            ("0x1::table::Table", [key, value]) => format!(
+                "::aptos_client::Table<{}, {}>",
+                self.field_type(key),
+                self.field_type(value)
+            ),
+            (base, []) => match self.struct_path(base) {
+                Some(path) => path,
+                None => "::aptos_client::serde_json::Value".to_string(),
+            },
+            _ => "::aptos_client::serde_json::Value".to_string(),
+        }
+    }
+
+    // 同一批模块中非泛型的 struct
+    fn struct_path(&self, ty: &str) -> Option<String> {
+        let mut parts = ty.split("::");
+        let address = AccountAddress::from_hex_literal(parts.next()?).ok()?;
+        let module = parts.next()?;
+        let name = parts.next()?;
+        if !self
+            .known_structs
+            .contains(&(address, module.to_string(), name.to_string()))
+        {
+            return None;
+        }
+        if address == self.module.address && module == self.module.name {
+            Some(name.to_string())
+        } else {
+            Some(format!("super::{}::{}", rust_ident(module), name))
+        }
+    }
+}
+
+// entry / view 参数的 Rust 类型
+fn argument_type(ty: &str) -> String {
+    let (base, type_args) = split_type(ty);
+    match (base, type_args.as_slice()) {
+        ("bool", []) | ("u8", []) | ("u16", []) | ("u32", []) | ("u64", []) | ("u128", []) => {
+            base.to_string()
+        }
+        ("u256", []) => "::aptos_client::move_core_types::u256::U256".to_string(),
+        ("address", []) => "AccountAddress".to_string(),
+        ("0x1::string::String", []) => "String".to_string(),
+        ("vector", [inner]) => format!("Vec<{}>", argument_type(inner)),
+        ("0x1::option::Option", [inner]) => format!("Option<{}>", argument_type(inner)),
+        ("0x1::object::Object", [_]) => "AccountAddress".to_string(),
+        _ => "MoveArgument".to_string(),
+    }
+}
+
+// 将 argument_type 类型的变量转换为 MoveArgument 的表达式
+fn argument_expr(var: &str, ty: &str, depth: usize) -> String {
+    let (base, type_args) = split_type(ty);
+    match (base, type_args.as_slice()) {
+        ("vector", [inner]) if inner == "u8" => format!("MoveArgument::Bytes({})", var),
+        ("vector", [inner]) => {
+            let item = format!("v{}", depth);
+            format!(
+                "MoveArgument::Vector({}.into_iter().map(|{}| {}).collect())",
+                var,
+                item,
+                argument_expr(&item, inner, depth + 1)
+            )
+        }
+        ("0x1::option::Option", [inner]) => {
+            let item = format!("v{}", depth);
+            format!(
+                "MoveArgument::Option({}.map(|{}| Box::new({})))",
+                var,
+                item,
+                argument_expr(&item, inner, depth + 1)
+            )
+        }
+        _ if argument_type(ty) == "MoveArgument" => var.to_string(),
+        _ => format!("MoveArgument::from({})", var),
+    }
+}
+
+// 开头的 signer 参数个数
+fn signer_count(function: &MoveFunction) -> usize {
+    function.params.len() - function.non_signer_params().len()
+}
+
+// 与生成的 new / with_address 重名的函数加上 move_ 前缀
+fn method_name(name: &str, function_names: &HashSet<&str>) -> String {
+    let mut method = name.to_string();
+    while method == "new"
+        || method == "with_address"
+        || (method != name && function_names.contains(method.as_str()))
+    {
+        method = format!("move_{}", method);
+    }
+    rust_ident(&method)
+}
+
+fn ty_args_expr(function: &MoveFunction) -> &'static str {
+    if function.generic_type_params.is_empty() {
+        "Vec::<TypeTag>::new()"
+    } else {
+        "ty_args"
+    }
+}
+
+// "0x1::coin::Coin<T0, u64>" => ("0x1::coin::Coin", ["T0", "u64"])
+fn split_type(ty: &str) -> (&str, Vec<String>) {
+    let ty = ty.trim();
+    let start = match ty.find('<') {
+        Some(start) if ty.ends_with('>') => start,
+        _ => return (ty, vec![]),
+    };
+    let mut type_args = vec![];
+    let mut depth = 0;
+    let mut current = String::new();
+    for c in ty[start + 1..ty.len() - 1].chars() {
+        match c {
+            '<' => depth += 1,
+            '>' => depth -= 1,
+            ',' if depth == 0 => {
+                type_args.push(current.trim().to_string());
+                current.clear();
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    type_args.push(current.trim().to_string());
+    (&ty[..start], type_args)
+}
+
+fn camel_case(name: &str) -> String {
+    name.split('_')
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+fn rust_ident(name: &str) -> String {
+    const KEYWORDS: &[&str] = &[
+        "as", "async", "await", "box", "break", "const", "continue", "do", "dyn", "else", "enum",
+        "extern", "false", "final", "fn", "for", "if", "impl", "in", "let", "loop", "macro",
+        "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return", "static",
+        "struct", "trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use", "virtual",
+        "where", "while", "yield",
+    ];
+    match name {
+        "self" | "Self" | "super" | "crate" => format!("{}_", name),
+        name if KEYWORDS.contains(&name) => format!("r#{}", name),
+        name => name.to_string(),
+    }
+}
+
+// 由编译后的模块构造与 REST API 相同的 ABI
+fn module_abi(module: &CompiledModule) -> MoveModule {
+    let exposed_functions = module
+        .function_defs()
+        .iter()
+        .filter(|def| def.visibility != Visibility::Private || def.is_entry)
+        .map(|def| {
+            let handle = module.function_handle_at(def.function);
+            MoveFunction {
+                name: module.identifier_at(handle.name).to_string(),
+                visibility: match def.visibility {
+                    Visibility::Public => "public",
+                    Visibility::Friend => "friend",
+                    Visibility::Private => "private",
+                }
+                .to_string(),
+                is_entry: def.is_entry,
+                is_view: false,
+                generic_type_params: handle
+                    .type_parameters
+                    .iter()
+                    .map(|abilities| MoveGenericTypeParam {
+                        constraints: ability_names(*abilities),
+                    })
+                    .collect(),
+                params: module
+                    .signature_at(handle.parameters)
+                    .0
+                    .iter()
+                    .map(|token| signature_token_string(module, token))
+                    .collect(),
+                return_types: module
+                    .signature_at(handle.return_)
+                    .0
+                    .iter()
+                    .map(|token| signature_token_string(module, token))
+                    .collect(),
+            }
+        })
+        .collect();
+    let structs = module
+        .struct_defs()
+        .iter()
+        .map(|def| {
+            let handle = module.struct_handle_at(def.struct_handle);
+            let (is_native, fields) = match &def.field_information {
+                StructFieldInformation::Native => (true, vec![]),
+                StructFieldInformation::Declared(fields) => (
+                    false,
+                    fields
+                        .iter()
+                        .map(|field| MoveStructField {
+                            name: module.identifier_at(field.name).to_string(),
+                            field_type: signature_token_string(module, &field.signature.0),
+                        })
+                        .collect(),
+                ),
+            };
+            MoveStruct {
+                name: module.identifier_at(handle.name).to_string(),
+                is_native,
+                abilities: ability_names(handle.abilities),
+                generic_type_params: handle
+                    .type_parameters
+                    .iter()
+                    .map(|param| MoveGenericTypeParam {
+                        constraints: ability_names(param.constraints),
+                    })
+                    .collect(),
+                fields,
+            }
+        })
+        .collect();
+    let self_id = module.self_id();
+    MoveModule {
+        address: *self_id.address(),
+        name: self_id.name().to_string(),
+        friends: module
+            .friend_decls()
+            .iter()
+            .map(|friend| {
+                format!(
+                    "{}::{}",
+                    module
+                        .address_identifier_at(friend.address)
+                        .to_hex_literal(),
+                    module.identifier_at(friend.name)
+                )
+            })
+            .collect(),
+        exposed_functions,
+        structs,
+    }
+}
+
+fn ability_names(abilities: AbilitySet) -> Vec<String> {
+    abilities
+        .into_iter()
+        .map(|ability| {
+            match ability {
+                Ability::Copy => "copy",
+                Ability::Drop => "drop",
+                Ability::Store => "store",
+                Ability::Key => "key",
+            }
+            .to_string()
+        })
+        .collect()
+}
+
+fn signature_token_string(module: &CompiledModule, token: &SignatureToken) -> String {
+    match token {
+        SignatureToken::Bool => "bool".to_string(),
+        SignatureToken::U8 => "u8".to_string(),
+        SignatureToken::U16 => "u16".to_string(),
+        SignatureToken::U32 => "u32".to_string(),
+        SignatureToken::U64 => "u64".to_string(),
+        SignatureToken::U128 => "u128".to_string(),
+        SignatureToken::U256 => "u256".to_string(),
+        SignatureToken::Address => "address".to_string(),
+        SignatureToken::Signer => "signer".to_string(),
+        SignatureToken::Vector(inner) => {
+            format!("vector<{}>", signature_token_string(module, inner))
+        }
+        SignatureToken::Struct(idx) => struct_name(module, *idx, &[]),
+        SignatureToken::StructInstantiation(idx, type_args) => struct_name(module, *idx, type_args),
+        SignatureToken::Reference(inner) => format!("&{}", signature_token_string(module, inner)),
+        SignatureToken::MutableReference(inner) => {
+            format!("&mut {}", signature_token_string(module, inner))
+        }
+        SignatureToken::TypeParameter(i) => format!("T{}", i),
+    }
+}
+
+fn struct_name(
+    module: &CompiledModule,
+    idx: StructHandleIndex,
+    type_args: &[SignatureToken],
+) -> String {
+    let handle = module.struct_handle_at(idx);
+    let module_handle = module.module_handle_at(handle.module);
+    let name = format!(
+        "{}::{}::{}",
+        module
+            .address_identifier_at(module_handle.address)
+            .to_hex_literal(),
+        module.identifier_at(module_handle.name),
+        module.identifier_at(handle.name)
+    );
+    if type_args.is_empty() {
+        name
+    } else {
+        let type_args: Vec<String> = type_args
+            .iter()
+            .map(|token| signature_token_string(module, token))
+            .collect();
+        format!("{}<{}>", name, type_args.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_from_abi_file() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata/codegen/counter_abi.json");
+        assert_eq!(
+            generate_bindings_from_abi_file(path).unwrap(),
+            include_str!("../testdata/codegen/counter.rs.golden")
+        );
+    }
+
+    #[test]
+    fn method_names_avoid_constructors() {
+        let names: HashSet<&str> = ["new", "move_new", "with_address", "type"]
+            .into_iter()
+            .collect();
+        assert_eq!(method_name("new", &names), "move_move_new");
+        assert_eq!(method_name("move_new", &names), "move_new");
+        assert_eq!(method_name("with_address", &names), "move_with_address");
+        assert_eq!(method_name("type", &names), "r#type");
+    }
+
+    #[test]
+    fn split_nested_types() {
+        assert_eq!(
+            split_type("0x1::table::Table<address, vector<0x1::coin::Coin<T0>>>"),
+            (
+                "0x1::table::Table",
+                vec![
+                    "address".to_string(),
+                    "vector<0x1::coin::Coin<T0>>".to_string()
+                ]
+            )
+        );
+        assert_eq!(split_type("u64"), ("u64", vec![]));
+    }
+}
@@ -1,7 +1,17 @@
 use aptos_types::account_address::AccountAddress;
+use move_core_types::{
+    identifier::Identifier,
+    language_storage::{ModuleId, TypeTag},
+};
 use serde::Deserialize;
+use std::collections::HashMap;
 
-use crate::{client::Client, types::U64};
+use crate::{
+    client::Client,
+    fungible_asset::FungibleAssetClient,
+    type_tag::{format_type_tag, IntoTypeTag},
+    types::{struct_type_tag, LocalAccount, MoveOption, ObjectRef, U128, U64},
+};
 
 #[derive(Debug)]
 pub struct CoinClient {
@@ -17,17 +27,322 @@ impl CoinClient {
 
     // APT 余额
     pub fn account_balance(&self, account_address: AccountAddress) -> Result<u64, anyhow::Error> {
+        self.balance(account_address, aptos_coin_type())
+    }
+
+    // 任意 coin 余额, coin_type 如 0x1::aptos_coin::AptosCoin
+    pub fn balance(
+        &self,
+        account_address: AccountAddress,
+        coin_type: impl IntoTypeTag,
+    ) -> Result<u64, anyhow::Error> {
+        let coin_type = coin_type.into_type_tag()?;
         Ok(self
             .client
-            .account_resource::<Balance>(
-                account_address,
-                "0x1::coin::CoinStore<0x1::aptos_coin::AptosCoin>",
-            )?
+            .account_resource::<Balance>(account_address, coin_store_type(&coin_type))?
             .data
             .coin
             .value
             .0)
     }
+
+    // 按 coin 精度格式化的余额
+    pub fn formatted_balance(
+        &self,
+        account_address: AccountAddress,
+        coin_type: impl IntoTypeTag,
+    ) -> Result<String, anyhow::Error> {
+        let coin_type = coin_type.into_type_tag()?;
+        let balance = self.balance(account_address, &coin_type)?;
+        let decimals = self.coin_info(&coin_type)?.decimals;
+        Ok(format_amount(balance, decimals))
+    }
+
+    // 是否已注册 CoinStore
+    pub fn is_registered(
+        &self,
+        account_address: AccountAddress,
+        coin_type: impl IntoTypeTag,
+    ) -> Result<bool, anyhow::Error> {
+        let coin_type = coin_type.into_type_tag()?;
+        Ok(self
+            .client
+            .account_resource_opt::<Balance>(account_address, coin_store_type(&coin_type))?
+            .is_some())
+    }
+
+    // coin 元数据, 存放在 coin 类型所在地址的 0x1::coin::CoinInfo<T>
+    pub fn coin_info(&self, coin_type: impl IntoTypeTag) -> Result<CoinInfo, anyhow::Error> {
+        let coin_type = coin_type.into_type_tag()?;
+        let coin_address = match &coin_type {
+            TypeTag::Struct(struct_tag) => struct_tag.address,
+            _ => return Err(anyhow::anyhow!("coin type must be a struct: {}", coin_type)),
+        };
+        let info = self
+            .client
+            .account_resource::<CoinInfoResource>(
+                coin_address,
+                struct_type_tag(AccountAddress::ONE, "coin", "CoinInfo", vec![coin_type]),
+            )?
+            .data;
+        let supply = match info.supply.as_option() {
+            Some(supply) => Some(self.optional_aggregator_value(supply)?),
+            None => None,
+        };
+        Ok(CoinInfo {
+            name: info.name,
+            symbol: info.symbol,
+            decimals: info.decimals,
+            supply,
+        })
+    }
+
+    // 转账, 接收方账户不存在时自动创建, 返回交易 hash
+    // 需要等待上链结果时调用 client.wait_for_committed_transaction
+    pub fn transfer(
+        &self,
+        sender: LocalAccount,
+        receiver: AccountAddress,
+        amount: u64,
+        coin_type: impl IntoTypeTag,
+    ) -> Result<String, anyhow::Error> {
+        let coin_type = coin_type.into_type_tag()?;
+        self.submit_entry_function(
+            sender,
+            "aptos_account",
+            "transfer_coins",
+            vec![coin_type],
+            vec![bcs::to_bytes(&receiver)?, bcs::to_bytes(&amount)?],
+        )
+    }
+
+    // 注册 CoinStore
+    pub fn register(
+        &self,
+        account: LocalAccount,
+        coin_type: impl IntoTypeTag,
+    ) -> Result<String, anyhow::Error> {
+        let coin_type = coin_type.into_type_tag()?;
+        self.submit_entry_function(account, "managed_coin", "register", vec![coin_type], vec![])
+    }
+
+    // 创建账户
+    pub fn create_account(
+        &self,
+        account: LocalAccount,
+        new_account: AccountAddress,
+    ) -> Result<String, anyhow::Error> {
+        self.submit_entry_function(
+            account,
+            "aptos_account",
+            "create_account",
+            vec![],
+            vec![bcs::to_bytes(&new_account)?],
+        )
+    }
+
+    // 已迁移 coin 对应的 FA metadata 地址, 未迁移时为 None
+    pub fn paired_metadata(
+        &self,
+        coin_type: impl IntoTypeTag,
+    ) -> Result<Option<AccountAddress>, anyhow::Error> {
+        let coin_type = coin_type.into_type_tag()?;
+        let values = self.client.view(
+            "0x1::coin::paired_metadata",
+            vec![format_type_tag(&coin_type)],
+            vec![],
+            None,
+        )?;
+        let metadata = match values.into_iter().next() {
+            Some(value) => serde_json::from_value::<MoveOption<ObjectRef>>(value)?,
+            None => return Ok(None),
+        };
+        Ok(metadata.into_option().map(|object| object.inner))
+    }
+
+    // coin 与其配对 FA 的余额之和
+    pub fn total_balance(
+        &self,
+        account_address: AccountAddress,
+        coin_type: impl IntoTypeTag,
+    ) -> Result<u64, anyhow::Error> {
+        let coin_type = coin_type.into_type_tag()?;
+        let coin_balance = match self
+            .client
+            .account_resource_opt::<Balance>(account_address, coin_store_type(&coin_type))?
+        {
+            Some(store) => store.data.coin.value.0,
+            None => 0,
+        };
+        let fa_balance = match self.paired_metadata(&coin_type)? {
+            Some(metadata) => FungibleAssetClient {
+                client: self.client.clone(),
+            }
+            .balance(account_address, metadata)?,
+            None => 0,
+        };
+        coin_balance.checked_add(fa_balance).ok_or_else(|| {
+            anyhow::anyhow!(
+                "total balance overflows u64: {} + {}",
+                coin_balance,
+                fa_balance
+            )
+        })
+    }
+
+    // 转入记录, 来自 CoinStore<T> 的 deposit_events
+    // 每个不同的交易版本额外请求一次 transaction_by_version 获取时间戳, limit 决定请求数量
+    pub fn deposits(
+        &self,
+        account_address: AccountAddress,
+        coin_type: impl IntoTypeTag,
+        start: Option<u64>,
+        limit: Option<u64>,
+    ) -> Result<Vec<CoinActivity>, anyhow::Error> {
+        let coin_type = coin_type.into_type_tag()?;
+        self.coin_activities(account_address, &coin_type, "deposit_events", start, limit)
+    }
+
+    // 转出记录, 来自 CoinStore<T> 的 withdraw_events
+    // 与 deposits 相同, 每个不同的交易版本请求一次时间戳
+    pub fn withdrawals(
+        &self,
+        account_address: AccountAddress,
+        coin_type: impl IntoTypeTag,
+        start: Option<u64>,
+        limit: Option<u64>,
+    ) -> Result<Vec<CoinActivity>, anyhow::Error> {
+        let coin_type = coin_type.into_type_tag()?;
+        self.coin_activities(account_address, &coin_type, "withdraw_events", start, limit)
+    }
+
+    // 以下为私有方法
+    fn coin_activities(
+        &self,
+        account_address: AccountAddress,
+        coin_type: &TypeTag,
+        field_name: &str,
+        start: Option<u64>,
+        limit: Option<u64>,
+    ) -> Result<Vec<CoinActivity>, anyhow::Error> {
+        let events = self.client.events_by_event_handle::<CoinEventData>(
+            account_address,
+            coin_store_type(coin_type),
+            field_name,
+            limit,
+            start.map(U64),
+        )?;
+        // 同一交易中的多个事件只查询一次时间戳
+        let mut timestamps = HashMap::new();
+        let mut activities = vec![];
+        for event in events {
+            let version = event.version.0;
+            let timestamp = match timestamps.get(&version) {
+                Some(timestamp) => *timestamp,
+                None => {
+                    let timestamp = self
+                        .client
+                        .transaction_by_version(version)?
+                        .timestamp
+                        .ok_or_else(|| anyhow::anyhow!("transaction {} has no timestamp", version))?
+                        .0;
+                    timestamps.insert(version, timestamp);
+                    timestamp
+                }
+            };
+            activities.push(CoinActivity {
+                version,
+                timestamp,
+                sequence_number: event.sequence_number.0,
+                amount: event.data.amount.0,
+            });
+        }
+        Ok(activities)
+    }
+
+    fn submit_entry_function(
+        &self,
+        account: LocalAccount,
+        module: &str,
+        function: &str,
+        ty_args: Vec<TypeTag>,
+        args: Vec<Vec<u8>>,
+    ) -> Result<String, anyhow::Error> {
+        self.client.submit_entry_function(
+            account,
+            ModuleId::new(AccountAddress::ONE, Identifier::new(module)?),
+            function,
+            ty_args,
+            args,
+        )
+    }
+
+    fn optional_aggregator_value(
+        &self,
+        supply: &OptionalAggregator,
+    ) -> Result<u128, anyhow::Error> {
+        if let Some(integer) = supply.integer.as_option() {
+            return Ok(integer.value.0);
+        }
+        match supply.aggregator.as_option() {
+            Some(aggregator) => {
+                let value = self.client.table_item::<U128>(
+                    &aggregator.handle.to_hex_literal(),
+                    "address",
+                    "u128",
+                    serde_json::json!(aggregator.key.to_hex_literal()),
+                    None,
+                )?;
+                Ok(value.0)
+            }
+            None => Err(anyhow::anyhow!("empty coin supply")),
+        }
+    }
+}
+
+/// `TypeTag` of `0x1::aptos_coin::AptosCoin`
+pub fn aptos_coin_type() -> TypeTag {
+    struct_type_tag(AccountAddress::ONE, "aptos_coin", "AptosCoin", vec![])
+}
+
+/// Format an on-chain amount with the coin decimals, e.g. 150000000 with 8 decimals is "1.50000000"
+pub fn format_amount(amount: u64, decimals: u8) -> String {
+    if decimals == 0 {
+        return amount.to_string();
+    }
+    let digits = format!("{:0>width$}", amount, width = decimals as usize + 1);
+    let (integer, fraction) = digits.split_at(digits.len() - decimals as usize);
+    format!("{}.{}", integer, fraction)
+}
+
+fn coin_store_type(coin_type: &TypeTag) -> TypeTag {
+    struct_type_tag(
+        AccountAddress::ONE,
+        "coin",
+        "CoinStore",
+        vec![coin_type.clone()],
+    )
+}
+
+/// A deposit or withdrawal of a coin, joined to its transaction
+#[derive(Debug, Clone)]
+pub struct CoinActivity {
+    /// Version of the transaction that emitted the event
+    pub version: u64,
+    /// Timestamp of the transaction in microseconds
+    pub timestamp: u64,
+    /// Sequence number of the event in its event handle
+    pub sequence_number: u64,
+    pub amount: u64,
+}
+
+#[derive(Debug)]
+pub struct CoinInfo {
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+    /// None if the coin does not track its supply
+    pub supply: Option<u128>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -39,3 +354,70 @@ pub struct AptosCoin {
 pub struct Balance {
     pub coin: AptosCoin,
 }
+
+// 以下私有方法使用
+#[derive(Debug, Deserialize)]
+struct CoinEventData {
+    pub amount: U64,
+}
+
+#[derive(Debug, Deserialize)]
+struct CoinInfoResource {
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+    pub supply: MoveOption<OptionalAggregator>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OptionalAggregator {
+    pub aggregator: MoveOption<Aggregator>,
+    pub integer: MoveOption<Integer>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Aggregator {
+    pub handle: AccountAddress,
+    pub key: AccountAddress,
+}
+
+#[derive(Debug, Deserialize)]
+struct Integer {
+    pub value: U128,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_amount_pads_fraction() {
+        assert_eq!(format_amount(150000000, 8), "1.50000000");
+        assert_eq!(format_amount(1, 8), "0.00000001");
+        assert_eq!(format_amount(0, 8), "0.00000000");
+        assert_eq!(format_amount(123456789012, 6), "123456.789012");
+    }
+
+    #[test]
+    fn format_amount_without_decimals() {
+        assert_eq!(format_amount(42, 0), "42");
+        assert_eq!(format_amount(u64::MAX, 0), u64::MAX.to_string());
+    }
+
+    #[test]
+    fn format_amount_max_value() {
+        assert_eq!(format_amount(u64::MAX, 8), "184467440737.09551615");
+    }
+
+    #[test]
+    fn aptos_coin_type_string() {
+        assert_eq!(
+            format_type_tag(&aptos_coin_type()),
+            "0x1::aptos_coin::AptosCoin"
+        );
+        assert_eq!(
+            format_type_tag(&coin_store_type(&aptos_coin_type())),
+            "0x1::coin::CoinStore<0x1::aptos_coin::AptosCoin>"
+        );
+    }
+}
@@ -0,0 +1,447 @@
+use aptos_types::account_address::AccountAddress;
+use move_core_types::{
+    identifier::Identifier,
+    language_storage::{ModuleId, TypeTag},
+};
+use serde::Deserialize;
+
+use crate::{
+    client::{Client, Transaction},
+    entry_function::{EntryFunctionCall, MoveArgument},
+    object::{create_object_address, ObjectCore},
+    property_map::{PropertyMap, PropertyValue},
+    types::{struct_type_tag, LocalAccount, ObjectRef},
+};
+
+/// Client of the object based Digital Asset standard (`0x4::collection`, `0x4::token`)
+#[derive(Debug)]
+pub struct DigitalAssetClient {
+    pub client: Client,
+}
+
+impl DigitalAssetClient {
+    pub fn new(rest_url: &str) -> Self {
+        Self {
+            client: Client::new(rest_url),
+        }
+    }
+
+    // 创建 collection, aptos_token 只能创建固定数量的 collection, max_supply 为 None 时取 u64::MAX
+    pub fn create_collection(
+        &self,
+        creator: LocalAccount,
+        name: &str,
+        description: &str,
+        uri: &str,
+        options: &DigitalAssetCollectionOptions,
+    ) -> Result<String, anyhow::Error> {
+        self.submit_aptos_token(
+            creator,
+            "create_collection",
+            vec![],
+            vec![
+                description.into(),
+                // aptos_token 只提供固定数量的 collection, 不限数量时取 u64::MAX
+                options.max_supply.unwrap_or(u64::MAX).into(),
+                name.into(),
+                uri.into(),
+                options.mutable_description.into(),
+                options.mutable_royalty.into(),
+                options.mutable_uri.into(),
+                options.mutable_token_description.into(),
+                options.mutable_token_name.into(),
+                options.mutable_token_properties.into(),
+                options.mutable_token_uri.into(),
+                options.tokens_burnable_by_creator.into(),
+                options.tokens_freezable_by_creator.into(),
+                options.royalty_numerator.into(),
+                options.royalty_denominator.into(),
+            ],
+        )
+    }
+
+    // 铸造 token, token 地址由 GUID 生成, 交易上链后用 minted_tokens 读取
+    pub fn mint(
+        &self,
+        creator: LocalAccount,
+        collection_name: &str,
+        name: &str,
+        description: &str,
+        uri: &str,
+        properties: &PropertyMap,
+    ) -> Result<String, anyhow::Error> {
+        let (keys, values, types) = properties.encode()?;
+        self.submit_aptos_token(
+            creator,
+            "mint",
+            vec![],
+            vec![
+                collection_name.into(),
+                description.into(),
+                name.into(),
+                uri.into(),
+                MoveArgument::vector(keys),
+                MoveArgument::vector(types),
+                MoveArgument::vector(values),
+            ],
+        )
+    }
+
+    // 铸造不可转移的 token
+    pub fn mint_soul_bound(
+        &self,
+        creator: LocalAccount,
+        collection_name: &str,
+        name: &str,
+        description: &str,
+        uri: &str,
+        properties: &PropertyMap,
+        soul_bound_to: AccountAddress,
+    ) -> Result<String, anyhow::Error> {
+        let (keys, values, types) = properties.encode()?;
+        self.submit_aptos_token(
+            creator,
+            "mint_soul_bound",
+            vec![],
+            vec![
+                collection_name.into(),
+                description.into(),
+                name.into(),
+                uri.into(),
+                MoveArgument::vector(keys),
+                MoveArgument::vector(types),
+                MoveArgument::vector(values),
+                soul_bound_to.into(),
+            ],
+        )
+    }
+
+    // 转移 token, 使用 0x1::object::transfer
+    pub fn transfer(
+        &self,
+        owner: LocalAccount,
+        token: AccountAddress,
+        receiver: AccountAddress,
+    ) -> Result<String, anyhow::Error> {
+        self.client.submit_entry_function_call(
+            owner,
+            &EntryFunctionCall::new(
+                ModuleId::new(AccountAddress::ONE, Identifier::new("object")?),
+                "transfer",
+                vec![token_type()],
+                vec![token.into(), receiver.into()],
+            ),
+        )
+    }
+
+    // 创建者销毁 token
+    pub fn burn(
+        &self,
+        creator: LocalAccount,
+        token: AccountAddress,
+    ) -> Result<String, anyhow::Error> {
+        self.submit_aptos_token(creator, "burn", vec![token_type()], vec![token.into()])
+    }
+
+    pub fn set_description(
+        &self,
+        creator: LocalAccount,
+        token: AccountAddress,
+        description: &str,
+    ) -> Result<String, anyhow::Error> {
+        self.submit_aptos_token(
+            creator,
+            "set_description",
+            vec![token_type()],
+            vec![token.into(), description.into()],
+        )
+    }
+
+    pub fn set_uri(
+        &self,
+        creator: LocalAccount,
+        token: AccountAddress,
+        uri: &str,
+    ) -> Result<String, anyhow::Error> {
+        self.submit_aptos_token(
+            creator,
+            "set_uri",
+            vec![token_type()],
+            vec![token.into(), uri.into()],
+        )
+    }
+
+    // 添加属性
+    pub fn add_property(
+        &self,
+        creator: LocalAccount,
+        token: AccountAddress,
+        key: &str,
+        value: &PropertyValue,
+    ) -> Result<String, anyhow::Error> {
+        self.submit_aptos_token(
+            creator,
+            "add_property",
+            vec![token_type()],
+            vec![
+                token.into(),
+                key.into(),
+                value.type_name().into(),
+                value.to_bcs()?.into(),
+            ],
+        )
+    }
+
+    // 修改属性
+    pub fn update_property(
+        &self,
+        creator: LocalAccount,
+        token: AccountAddress,
+        key: &str,
+        value: &PropertyValue,
+    ) -> Result<String, anyhow::Error> {
+        self.submit_aptos_token(
+            creator,
+            "update_property",
+            vec![token_type()],
+            vec![
+                token.into(),
+                key.into(),
+                value.type_name().into(),
+                value.to_bcs()?.into(),
+            ],
+        )
+    }
+
+    // 删除属性
+    pub fn remove_property(
+        &self,
+        creator: LocalAccount,
+        token: AccountAddress,
+        key: &str,
+    ) -> Result<String, anyhow::Error> {
+        self.submit_aptos_token(
+            creator,
+            "remove_property",
+            vec![token_type()],
+            vec![token.into(), key.into()],
+        )
+    }
+
+    // token object 上的 0x4::token::Token
+    pub fn token(&self, token: AccountAddress) -> Result<DigitalAsset, anyhow::Error> {
+        Ok(self
+            .client
+            .account_resource::<DigitalAsset>(token, "0x4::token::Token")?
+            .data)
+    }
+
+    // collection object 上的 0x4::collection::Collection
+    pub fn collection(
+        &self,
+        collection: AccountAddress,
+    ) -> Result<DigitalAssetCollection, anyhow::Error> {
+        Ok(self
+            .client
+            .account_resource::<DigitalAssetCollection>(collection, "0x4::collection::Collection")?
+            .data)
+    }
+
+    // 等待交易上链, 返回其中铸造的 token 地址
+    pub fn minted_tokens(&self, txn_hash: &str) -> Result<Vec<AccountAddress>, anyhow::Error> {
+        minted_tokens(&self.client.wait_for_committed_transaction(txn_hash)?)
+    }
+
+    // object 当前的持有者
+    pub fn owner(&self, object: AccountAddress) -> Result<AccountAddress, anyhow::Error> {
+        Ok(self
+            .client
+            .account_resource::<ObjectCore>(object, "0x1::object::ObjectCore")?
+            .data
+            .owner)
+    }
+
+    // 以下为私有方法
+    fn submit_aptos_token(
+        &self,
+        account: LocalAccount,
+        function: &str,
+        ty_args: Vec<TypeTag>,
+        args: Vec<MoveArgument>,
+    ) -> Result<String, anyhow::Error> {
+        self.client.submit_entry_function_call(
+            account,
+            &EntryFunctionCall::new(
+                ModuleId::new(
+                    AccountAddress::from_hex_literal("0x4")?,
+                    Identifier::new("aptos_token")?,
+                ),
+                function,
+                ty_args,
+                args,
+            ),
+        )
+    }
+}
+
+/// Address of the collection `name` created by `creator`
+pub fn collection_address(creator: &AccountAddress, name: &str) -> AccountAddress {
+    create_object_address(creator, name.as_bytes())
+}
+
+/// Address of a named token, i.e. created by `0x4::token::create_named_token`
+///
+/// Tokens minted with `DigitalAssetClient::mint` are not named, their address comes from
+/// `DigitalAssetClient::minted_tokens`.
+pub fn token_address(
+    creator: &AccountAddress,
+    collection_name: &str,
+    token_name: &str,
+) -> AccountAddress {
+    create_object_address(
+        creator,
+        format!("{}::{}", collection_name, token_name).as_bytes(),
+    )
+}
+
+/// Addresses of the tokens minted by a committed transaction, from its collection mint events
+pub fn minted_tokens(transaction: &Transaction) -> Result<Vec<AccountAddress>, anyhow::Error> {
+    transaction
+        .events
+        .iter()
+        .filter(|event| {
+            event.event_type == "0x4::collection::MintEvent"
+                || event.event_type == "0x4::collection::Mint"
+        })
+        .map(|event| {
+            let token = event.data["token"]
+                .as_str()
+                .ok_or_else(|| anyhow::anyhow!("mint event without token: {}", event.data))?;
+            Ok(AccountAddress::from_hex_literal(token)?)
+        })
+        .collect()
+}
+
+fn token_type() -> TypeTag {
+    struct_type_tag(
+        AccountAddress::from_hex_literal("0x4").unwrap(),
+        "token",
+        "Token",
+        vec![],
+    )
+}
+
+/// Options of `DigitalAssetClient::create_collection`, defaults to an immutable collection with a
+/// maximum supply of `u64::MAX`
+#[derive(Debug, Clone)]
+pub struct DigitalAssetCollectionOptions {
+    /// Maximum number of tokens, `u64::MAX` if None
+    ///
+    /// `0x4::aptos_token::create_collection` always creates a fixed supply collection, so None is
+    /// still a fixed supply collection, tracking its supply, with a maximum of `u64::MAX`
+    pub max_supply: Option<u64>,
+    pub mutable_description: bool,
+    pub mutable_royalty: bool,
+    pub mutable_uri: bool,
+    pub mutable_token_description: bool,
+    pub mutable_token_name: bool,
+    pub mutable_token_properties: bool,
+    pub mutable_token_uri: bool,
+    pub tokens_burnable_by_creator: bool,
+    pub tokens_freezable_by_creator: bool,
+    pub royalty_numerator: u64,
+    pub royalty_denominator: u64,
+}
+
+impl Default for DigitalAssetCollectionOptions {
+    fn default() -> Self {
+        Self {
+            max_supply: None,
+            mutable_description: false,
+            mutable_royalty: false,
+            mutable_uri: false,
+            mutable_token_description: false,
+            mutable_token_name: false,
+            mutable_token_properties: false,
+            mutable_token_uri: false,
+            tokens_burnable_by_creator: false,
+            tokens_freezable_by_creator: false,
+            royalty_numerator: 0,
+            royalty_denominator: 1_000_000,
+        }
+    }
+}
+
+/// `0x4::token::Token`
+#[derive(Debug, Deserialize)]
+pub struct DigitalAsset {
+    pub collection: ObjectRef,
+    pub description: String,
+    pub name: String,
+    pub uri: String,
+}
+
+/// `0x4::collection::Collection`
+#[derive(Debug, Deserialize)]
+pub struct DigitalAssetCollection {
+    pub creator: AccountAddress,
+    pub description: String,
+    pub name: String,
+    pub uri: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn address(hex: &str) -> AccountAddress {
+        AccountAddress::from_hex_literal(hex).unwrap()
+    }
+
+    #[test]
+    fn collection_and_token_addresses() {
+        let creator = address("0xcafe");
+        // sha3_256(creator || name || 0xFE)
+        assert_eq!(
+            collection_address(&creator, "Aptos Monkeys"),
+            address("0x6e8111e1db0dcf209309b3e48ae71340ca4e4f0544d2fff43df7ae4f952d340a")
+        );
+        // seed 为 "{collection}::{name}"
+        assert_eq!(
+            token_address(&creator, "Aptos Monkeys", "Monkey #1"),
+            address("0x8c9c72f9433738571ec02d69fb5dc8a811289aa69efe904f971652b79b632938")
+        );
+        assert_eq!(
+            token_address(&creator, "Aptos Monkeys", "Monkey #1"),
+            create_object_address(&creator, b"Aptos Monkeys::Monkey #1")
+        );
+    }
+
+    #[test]
+    fn minted_tokens_from_events() {
+        let transaction = serde_json::from_value::<Transaction>(serde_json::json!({
+            "type": "user_transaction",
+            "sequence_number": "3",
+            "success": true,
+            "events": [
+                {
+                    "type": "0x1::object::TransferEvent",
+                    "data": {"from": "0xcafe", "object": "0xa1", "to": "0xcafe"}
+                },
+                {
+                    "type": "0x4::collection::MintEvent",
+                    "data": {"index": "1", "token": "0xa1"}
+                },
+                {
+                    "type": "0x4::collection::Mint",
+                    "data": {"collection": {"inner": "0xc0"}, "index": {"value": "2"}, "token": "0xa2"}
+                }
+            ]
+        }))
+        .unwrap();
+        assert_eq!(
+            minted_tokens(&transaction).unwrap(),
+            vec![address("0xa1"), address("0xa2")]
+        );
+    }
+}
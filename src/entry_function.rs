@@ -0,0 +1,641 @@
+use anyhow::Context;
+use aptos_types::{
+    account_address::AccountAddress,
+    transaction::{EntryFunction, TransactionPayload},
+};
+use move_core_types::{
+    identifier::Identifier,
+    language_storage::{ModuleId, TypeTag},
+    u256::U256,
+};
+use serde::{Serialize, Serializer};
+use std::{fmt, str::FromStr};
+
+use crate::{client::MoveModule, type_tag::format_type_tag};
+
+/// A typed entry function argument, BCS encoded when the payload is built
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MoveArgument {
+    Bool(bool),
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    U128(u128),
+    U256(U256),
+    Address(AccountAddress),
+    String(String),
+    Bytes(Vec<u8>),
+    Vector(Vec<MoveArgument>),
+    Option(Option<Box<MoveArgument>>),
+}
+
+impl MoveArgument {
+    pub fn vector<T: Into<MoveArgument>>(items: Vec<T>) -> Self {
+        Self::Vector(items.into_iter().map(Into::into).collect())
+    }
+
+    pub fn option<T: Into<MoveArgument>>(value: Option<T>) -> Self {
+        Self::Option(value.map(|v| Box::new(v.into())))
+    }
+
+    // 用于错误信息
+    pub fn type_name(&self) -> String {
+        match self {
+            Self::Bool(_) => "bool".to_string(),
+            Self::U8(_) => "u8".to_string(),
+            Self::U16(_) => "u16".to_string(),
+            Self::U32(_) => "u32".to_string(),
+            Self::U64(_) => "u64".to_string(),
+            Self::U128(_) => "u128".to_string(),
+            Self::U256(_) => "u256".to_string(),
+            Self::Address(_) => "address".to_string(),
+            Self::String(_) => "0x1::string::String".to_string(),
+            Self::Bytes(_) => "vector<u8>".to_string(),
+            Self::Vector(items) => match items.first() {
+                Some(item) => format!("vector<{}>", item.type_name()),
+                None => "vector<_>".to_string(),
+            },
+            Self::Option(value) => match value {
+                Some(value) => format!("0x1::option::Option<{}>", value.type_name()),
+                None => "0x1::option::Option<_>".to_string(),
+            },
+        }
+    }
+
+    /// Convert a JSON value into an argument of ABI type `ty`, e.g. `vector<u64>`
+    pub fn from_json(value: &serde_json::Value, ty: &str) -> Result<Self, anyhow::Error> {
+        let ty = ty.trim();
+        Ok(match ty {
+            "bool" => Self::Bool(
+                value
+                    .as_bool()
+                    .ok_or_else(|| anyhow::anyhow!("expected bool, got {}", value))?,
+            ),
+            "u8" => Self::U8(json_integer::<u8>(value)?),
+            "u16" => Self::U16(json_integer::<u16>(value)?),
+            "u32" => Self::U32(json_integer::<u32>(value)?),
+            "u64" => Self::U64(json_integer::<u64>(value)?),
+            "u128" => Self::U128(json_integer::<u128>(value)?),
+            "u256" => Self::U256(json_integer::<U256>(value)?),
+            "address" => Self::Address(json_address(value)?),
+            "0x1::string::String" => Self::String(
+                value
+                    .as_str()
+                    .ok_or_else(|| anyhow::anyhow!("expected string, got {}", value))?
+                    .to_string(),
+            ),
+            "vector<u8>" if value.is_string() => {
+                let s = value.as_str().unwrap_or_default();
+                Self::Bytes(hex::decode(s.trim_start_matches("0x"))?)
+            }
+            _ => {
+                if let Some(inner) = generic_inner(ty, "vector") {
+                    let items = value
+                        .as_array()
+                        .ok_or_else(|| anyhow::anyhow!("expected array, got {}", value))?;
+                    let mut arguments = vec![];
+                    for item in items {
+                        arguments.push(Self::from_json(item, inner)?);
+                    }
+                    Self::Vector(arguments)
+                } else if let Some(inner) = generic_inner(ty, "0x1::option::Option") {
+                    // null 或 {"vec": []} 为 None
+                    let value = match value.get("vec").and_then(|v| v.as_array()) {
+                        Some(items) => items.first().unwrap_or(&serde_json::Value::Null),
+                        None => value,
+                    };
+                    match value {
+                        serde_json::Value::Null => Self::Option(None),
+                        value => Self::Option(Some(Box::new(Self::from_json(value, inner)?))),
+                    }
+                } else if generic_inner(ty, "0x1::object::Object").is_some() {
+                    // 地址或 {"inner": "0x..."}
+                    Self::Address(json_address(value.get("inner").unwrap_or(value))?)
+                } else {
+                    return Err(anyhow::anyhow!("unsupported argument type: {}", ty));
+                }
+            }
+        })
+    }
+
+    /// JSON form used by the view API, integers above u32 as strings and bytes as hex
+    pub fn to_json(&self) -> serde_json::Value {
+        match self {
+            Self::Bool(v) => serde_json::Value::Bool(*v),
+            Self::U8(v) => serde_json::Value::from(*v),
+            Self::U16(v) => serde_json::Value::from(*v),
+            Self::U32(v) => serde_json::Value::from(*v),
+            Self::U64(v) => serde_json::Value::String(v.to_string()),
+            Self::U128(v) => serde_json::Value::String(v.to_string()),
+            Self::U256(v) => serde_json::Value::String(v.to_string()),
+            Self::Address(v) => serde_json::Value::String(v.to_hex_literal()),
+            Self::String(v) => serde_json::Value::String(v.clone()),
+            Self::Bytes(v) => serde_json::Value::String(format!("0x{}", hex::encode(v))),
+            Self::Vector(items) => {
+                serde_json::Value::Array(items.iter().map(|item| item.to_json()).collect())
+            }
+            Self::Option(value) => serde_json::json!({
+                "vec": value.iter().map(|v| v.to_json()).collect::<Vec<_>>(),
+            }),
+        }
+    }
+
+    /// Whether the argument can be passed as a parameter of ABI type `ty`, e.g. `vector<u64>`
+    pub fn matches(&self, ty: &str) -> bool {
+        let ty = ty.trim();
+        match self {
+            Self::Bool(_) => ty == "bool",
+            Self::U8(_) => ty == "u8",
+            Self::U16(_) => ty == "u16",
+            Self::U32(_) => ty == "u32",
+            Self::U64(_) => ty == "u64",
+            Self::U128(_) => ty == "u128",
+            Self::U256(_) => ty == "u256",
+            // Object<T> 以地址传入
+            Self::Address(_) => {
+                ty == "address" || generic_inner(ty, "0x1::object::Object").is_some()
+            }
+            Self::String(_) => ty == "0x1::string::String",
+            Self::Bytes(_) => ty == "vector<u8>",
+            Self::Vector(items) => match generic_inner(ty, "vector") {
+                Some(inner) => items.iter().all(|item| item.matches(inner)),
+                None => false,
+            },
+            Self::Option(value) => match generic_inner(ty, "0x1::option::Option") {
+                Some(inner) => value.as_ref().map_or(true, |value| value.matches(inner)),
+                None => false,
+            },
+        }
+    }
+}
+
+impl Serialize for MoveArgument {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Bool(v) => v.serialize(serializer),
+            Self::U8(v) => v.serialize(serializer),
+            Self::U16(v) => v.serialize(serializer),
+            Self::U32(v) => v.serialize(serializer),
+            Self::U64(v) => v.serialize(serializer),
+            Self::U128(v) => v.serialize(serializer),
+            Self::U256(v) => v.serialize(serializer),
+            Self::Address(v) => v.serialize(serializer),
+            Self::String(v) => v.serialize(serializer),
+            Self::Bytes(v) => v.serialize(serializer),
+            Self::Vector(v) => v.serialize(serializer),
+            // Move Option 为 struct { vec: vector<T> }, BCS 即 0 或 1 个元素的 vector
+            Self::Option(v) => v
+                .iter()
+                .map(|v| v.as_ref())
+                .collect::<Vec<&MoveArgument>>()
+                .serialize(serializer),
+        }
+    }
+}
+
+macro_rules! impl_from_for_move_argument {
+    ($($ty:ty => $variant:ident),* $(,)?) => {
+        $(
+            impl From<$ty> for MoveArgument {
+                fn from(v: $ty) -> Self {
+                    Self::$variant(v.into())
+                }
+            }
+        )*
+    };
+}
+
+impl_from_for_move_argument!(
+    bool => Bool,
+    u8 => U8,
+    u16 => U16,
+    u32 => U32,
+    u64 => U64,
+    u128 => U128,
+    U256 => U256,
+    AccountAddress => Address,
+    String => String,
+    &str => String,
+    Vec<u8> => Bytes,
+);
+
+/// An entry function call with typed arguments, checked against the module ABI before submission
+#[derive(Debug, Clone)]
+pub struct EntryFunctionCall {
+    pub module: ModuleId,
+    pub function: String,
+    pub ty_args: Vec<TypeTag>,
+    pub args: Vec<MoveArgument>,
+}
+
+impl EntryFunctionCall {
+    pub fn new(
+        module: ModuleId,
+        function: &str,
+        ty_args: Vec<TypeTag>,
+        args: Vec<MoveArgument>,
+    ) -> Self {
+        Self {
+            module,
+            function: function.to_string(),
+            ty_args,
+            args,
+        }
+    }
+
+    /// Convert JSON arguments into typed arguments by the parameter types of the function ABI
+    ///
+    /// u64, u128 and u256 are given as strings, vector<u8> as a hex string and Option as null or its value
+    pub fn from_json(
+        abi: &MoveModule,
+        function: &str,
+        ty_args: Vec<TypeTag>,
+        args: Vec<serde_json::Value>,
+    ) -> Result<Self, anyhow::Error> {
+        let module = ModuleId::new(abi.address, Identifier::new(abi.name.as_str())?);
+        let params = entry_function_params(abi, &module, function, &ty_args, args.len())?;
+        let mut arguments = vec![];
+        for (i, (arg, param)) in args.iter().zip(params).enumerate() {
+            arguments.push(
+                MoveArgument::from_json(arg, &param)
+                    .with_context(|| format!("argument {} of {}::{}", i, module, function))?,
+            );
+        }
+        Ok(Self::new(module, function, ty_args, arguments))
+    }
+
+    /// Check the function is an entry function and the type and value arguments match its ABI
+    pub fn check(&self, abi: &MoveModule) -> Result<(), anyhow::Error> {
+        let params = entry_function_params(
+            abi,
+            &self.module,
+            &self.function,
+            &self.ty_args,
+            self.args.len(),
+        )?;
+        for (i, (arg, param)) in self.args.iter().zip(params).enumerate() {
+            if !arg.matches(&param) {
+                return Err(anyhow::anyhow!(
+                    "argument {} of {}::{} expects {}, got {}",
+                    i,
+                    self.module,
+                    self.function,
+                    param,
+                    arg.type_name()
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Build the payload without checking the ABI
+    pub fn payload(&self) -> Result<TransactionPayload, anyhow::Error> {
+        let mut args = vec![];
+        for arg in &self.args {
+            args.push(bcs::to_bytes(arg)?);
+        }
+        Ok(TransactionPayload::EntryFunction(EntryFunction::new(
+            self.module.clone(),
+            Identifier::new(self.function.as_str())?,
+            self.ty_args.clone(),
+            args,
+        )))
+    }
+}
+
+// 整数可以是 JSON 数字或字符串
+fn json_integer<T: FromStr>(value: &serde_json::Value) -> Result<T, anyhow::Error>
+where
+    T::Err: fmt::Display,
+{
+    let s = match value {
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Number(n) => n.to_string(),
+        _ => return Err(anyhow::anyhow!("expected integer, got {}", value)),
+    };
+    s.parse::<T>()
+        .map_err(|e| anyhow::anyhow!("invalid integer {}: {}", s, e))
+}
+
+fn json_address(value: &serde_json::Value) -> Result<AccountAddress, anyhow::Error> {
+    let s = value
+        .as_str()
+        .ok_or_else(|| anyhow::anyhow!("expected address, got {}", value))?;
+    if s.starts_with("0x") {
+        Ok(AccountAddress::from_hex_literal(s)?)
+    } else {
+        Ok(AccountAddress::from_hex_literal(&format!("0x{}", s))?)
+    }
+}
+
+// 检查 entry function, 类型参数与参数个数, 返回代入类型参数后的非 signer 参数类型
+fn entry_function_params(
+    abi: &MoveModule,
+    module: &ModuleId,
+    function: &str,
+    ty_args: &[TypeTag],
+    args_len: usize,
+) -> Result<Vec<String>, anyhow::Error> {
+    let move_function = abi
+        .function(function)
+        .ok_or_else(|| anyhow::anyhow!("function {}::{} not found", module, function))?;
+    if !move_function.is_entry {
+        return Err(anyhow::anyhow!(
+            "{}::{} is not an entry function",
+            module,
+            function
+        ));
+    }
+    if move_function.generic_type_params.len() != ty_args.len() {
+        return Err(anyhow::anyhow!(
+            "{}::{} expects {} type arguments, got {}",
+            module,
+            function,
+            move_function.generic_type_params.len(),
+            ty_args.len()
+        ));
+    }
+    let params = move_function.non_signer_params();
+    if params.len() != args_len {
+        return Err(anyhow::anyhow!(
+            "{}::{} expects {} arguments, got {}",
+            module,
+            function,
+            params.len(),
+            args_len
+        ));
+    }
+    Ok(params
+        .iter()
+        .map(|param| substitute_generics(param, ty_args))
+        .collect())
+}
+
+// vector<u8> => Some("u8")
+fn generic_inner<'a>(ty: &'a str, name: &str) -> Option<&'a str> {
+    ty.strip_prefix(name)?.strip_prefix('<')?.strip_suffix('>')
+}
+
+// 将 ABI 参数中的 T0, T1 替换为实际的类型参数
+fn substitute_generics(ty: &str, ty_args: &[TypeTag]) -> String {
+    let mut result = String::new();
+    let mut token = String::new();
+    for c in ty.chars() {
+        if c.is_alphanumeric() || c == '_' || c == ':' {
+            token.push(c);
+        } else {
+            result.push_str(&substitute_token(&token, ty_args));
+            token.clear();
+            result.push(c);
+        }
+    }
+    result.push_str(&substitute_token(&token, ty_args));
+    result
+}
+
+fn substitute_token(token: &str, ty_args: &[TypeTag]) -> String {
+    match token
+        .strip_prefix('T')
+        .and_then(|i| i.parse::<usize>().ok())
+        .and_then(|i| ty_args.get(i))
+    {
+        Some(ty_arg) => format_type_tag(ty_arg),
+        None => token.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::struct_type_tag;
+    use serde_json::json;
+
+    fn bcs_of(value: serde_json::Value, ty: &str) -> Vec<u8> {
+        bcs::to_bytes(&MoveArgument::from_json(&value, ty).unwrap()).unwrap()
+    }
+
+    fn abi() -> MoveModule {
+        serde_json::from_value(json!({
+            "address": "0xcafe",
+            "name": "vault",
+            "friends": [],
+            "exposed_functions": [
+                {
+                    "name": "deposit",
+                    "visibility": "public",
+                    "is_entry": true,
+                    "generic_type_params": [{"constraints": []}],
+                    "params": ["&signer", "u64", "vector<T0>", "0x1::option::Option<address>"],
+                    "return": []
+                },
+                {
+                    "name": "balance",
+                    "visibility": "public",
+                    "is_entry": false,
+                    "is_view": true,
+                    "generic_type_params": [],
+                    "params": ["address"],
+                    "return": ["u64"]
+                }
+            ],
+            "structs": []
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn integers_from_strings_and_numbers() {
+        assert_eq!(
+            bcs_of(json!("1000"), "u64"),
+            vec![0xe8, 0x03, 0, 0, 0, 0, 0, 0]
+        );
+        assert_eq!(
+            bcs_of(json!(1000), "u64"),
+            vec![0xe8, 0x03, 0, 0, 0, 0, 0, 0]
+        );
+        assert_eq!(bcs_of(json!(7), "u8"), vec![7]);
+        assert_eq!(bcs_of(json!(258), "u16"), vec![2, 1]);
+        assert_eq!(bcs_of(json!("65536"), "u32"), vec![0, 0, 1, 0]);
+
+        let mut u128_bytes = vec![0u8; 16];
+        u128_bytes[0] = 1;
+        u128_bytes[15] = 0x80;
+        assert_eq!(
+            bcs_of(json!("170141183460469231731687303715884105729"), "u128"),
+            u128_bytes
+        );
+
+        let mut u256_bytes = vec![0u8; 32];
+        u256_bytes[0] = 0xff;
+        u256_bytes[1] = 0x01;
+        assert_eq!(bcs_of(json!("511"), "u256"), u256_bytes);
+    }
+
+    #[test]
+    fn address_and_object() {
+        let mut address = vec![0u8; 32];
+        address[31] = 1;
+        assert_eq!(bcs_of(json!("0x1"), "address"), address);
+        assert_eq!(bcs_of(json!("1"), "address"), address);
+        assert_eq!(
+            bcs_of(
+                json!({"inner": "0x1"}),
+                "0x1::object::Object<0x1::fungible_asset::Metadata>"
+            ),
+            address
+        );
+        assert_eq!(
+            bcs_of(json!("0x1"), "0x1::object::Object<0x1::object::ObjectCore>"),
+            address
+        );
+    }
+
+    #[test]
+    fn strings_and_bytes() {
+        assert_eq!(
+            bcs_of(json!("abc"), "0x1::string::String"),
+            vec![3, b'a', b'b', b'c']
+        );
+        assert_eq!(bcs_of(json!("0x0102"), "vector<u8>"), vec![2, 1, 2]);
+        assert_eq!(bcs_of(json!([1, 2]), "vector<u8>"), vec![2, 1, 2]);
+    }
+
+    #[test]
+    fn nested_vectors() {
+        assert_eq!(
+            bcs_of(json!([["1"], []]), "vector<vector<u64>>"),
+            vec![2, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0]
+        );
+        assert_eq!(
+            bcs_of(json!(["a", "bc"]), "vector<0x1::string::String>"),
+            vec![2, 1, b'a', 2, b'b', b'c']
+        );
+    }
+
+    #[test]
+    fn options() {
+        assert_eq!(bcs_of(json!(null), "0x1::option::Option<u64>"), vec![0]);
+        assert_eq!(
+            bcs_of(json!({"vec": []}), "0x1::option::Option<u64>"),
+            vec![0]
+        );
+        assert_eq!(
+            bcs_of(json!("5"), "0x1::option::Option<u64>"),
+            vec![1, 5, 0, 0, 0, 0, 0, 0, 0]
+        );
+        assert_eq!(
+            bcs_of(json!({"vec": ["5"]}), "0x1::option::Option<u64>"),
+            vec![1, 5, 0, 0, 0, 0, 0, 0, 0]
+        );
+    }
+
+    #[test]
+    fn type_mismatches() {
+        assert!(MoveArgument::from_json(&json!("256"), "u8").is_err());
+        assert!(MoveArgument::from_json(&json!("-1"), "u64").is_err());
+        assert!(MoveArgument::from_json(&json!(1.5), "u64").is_err());
+        assert!(MoveArgument::from_json(&json!("true"), "bool").is_err());
+        assert!(MoveArgument::from_json(&json!(1), "0x1::string::String").is_err());
+        assert!(MoveArgument::from_json(&json!("1"), "vector<u64>").is_err());
+        assert!(MoveArgument::from_json(&json!(["x"]), "vector<u64>").is_err());
+        assert!(MoveArgument::from_json(&json!(1), "address").is_err());
+        assert!(MoveArgument::from_json(&json!("0xzz"), "vector<u8>").is_err());
+        assert!(MoveArgument::from_json(&json!({}), "0x1::m::S").is_err());
+    }
+
+    #[test]
+    fn matches_abi_types() {
+        assert!(MoveArgument::U64(1).matches("u64"));
+        assert!(!MoveArgument::U64(1).matches("u128"));
+        assert!(MoveArgument::Address(AccountAddress::ONE)
+            .matches("0x1::object::Object<0x1::object::ObjectCore>"));
+        assert!(MoveArgument::vector(vec![1u64, 2]).matches("vector<u64>"));
+        assert!(!MoveArgument::vector(vec![1u64]).matches("vector<u8>"));
+        assert!(MoveArgument::Vector(vec![]).matches("vector<u64>"));
+        assert!(MoveArgument::option(None::<u64>).matches("0x1::option::Option<u64>"));
+        assert!(!MoveArgument::option(Some("a")).matches("0x1::option::Option<u64>"));
+        assert!(MoveArgument::from("a").matches("0x1::string::String"));
+    }
+
+    #[test]
+    fn substitutes_type_arguments() {
+        let coin = struct_type_tag(AccountAddress::ONE, "aptos_coin", "AptosCoin", vec![]);
+        assert_eq!(
+            substitute_generics("vector<T0>", &[TypeTag::U64]),
+            "vector<u64>"
+        );
+        assert_eq!(
+            substitute_generics("0x1::coin::Coin<T1>", &[TypeTag::Bool, coin]),
+            "0x1::coin::Coin<0x1::aptos_coin::AptosCoin>"
+        );
+        // 超出范围的参数保持不变
+        assert_eq!(substitute_generics("T2", &[TypeTag::U8]), "T2");
+        assert_eq!(
+            substitute_generics("0x1::m::T0x", &[TypeTag::U8]),
+            "0x1::m::T0x"
+        );
+    }
+
+    #[test]
+    fn call_from_json_with_abi() {
+        let call = EntryFunctionCall::from_json(
+            &abi(),
+            "deposit",
+            vec![TypeTag::U8],
+            vec![json!("10"), json!([1, 2]), json!(null)],
+        )
+        .unwrap();
+        assert_eq!(
+            call.args,
+            vec![
+                MoveArgument::U64(10),
+                MoveArgument::Vector(vec![MoveArgument::U8(1), MoveArgument::U8(2)]),
+                MoveArgument::Option(None),
+            ]
+        );
+        assert!(call.check(&abi()).is_ok());
+        match call.payload().unwrap() {
+            TransactionPayload::EntryFunction(entry_function) => {
+                assert_eq!(
+                    entry_function.args(),
+                    &[vec![10, 0, 0, 0, 0, 0, 0, 0], vec![2, 1, 2], vec![0]]
+                );
+            }
+            _ => panic!("expected an entry function payload"),
+        }
+    }
+
+    #[test]
+    fn call_from_json_errors() {
+        // 参数个数
+        assert!(EntryFunctionCall::from_json(
+            &abi(),
+            "deposit",
+            vec![TypeTag::U8],
+            vec![json!("1")]
+        )
+        .is_err());
+        // 类型参数个数
+        assert!(EntryFunctionCall::from_json(
+            &abi(),
+            "deposit",
+            vec![],
+            vec![json!("1"), json!([]), json!(null)]
+        )
+        .is_err());
+        // 非 entry function
+        assert!(
+            EntryFunctionCall::from_json(&abi(), "balance", vec![], vec![json!("0x1")]).is_err()
+        );
+        assert!(EntryFunctionCall::from_json(&abi(), "missing", vec![], vec![]).is_err());
+        // 类型参数代入后的类型不符
+        assert!(EntryFunctionCall::from_json(
+            &abi(),
+            "deposit",
+            vec![TypeTag::U8],
+            vec![json!("1"), json!([300]), json!(null)]
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,99 @@
+use aptos_types::account_address::AccountAddress;
+use move_core_types::{identifier::Identifier, language_storage::ModuleId};
+use serde::Deserialize;
+
+use crate::{
+    client::Client,
+    object::create_user_derived_object_address,
+    types::{struct_type_tag, LocalAccount, ObjectRef, U64},
+};
+
+#[derive(Debug)]
+pub struct FungibleAssetClient {
+    pub client: Client,
+}
+
+impl FungibleAssetClient {
+    pub fn new(rest_url: &str) -> Self {
+        Self {
+            client: Client::new(rest_url),
+        }
+    }
+
+    // FA 余额, 读取 owner 的 primary store, 不存在时为 0
+    pub fn balance(
+        &self,
+        owner: AccountAddress,
+        metadata: AccountAddress,
+    ) -> Result<u64, anyhow::Error> {
+        Ok(self
+            .client
+            .account_resource_opt::<FungibleStore>(
+                primary_store_address(&owner, &metadata),
+                "0x1::fungible_asset::FungibleStore",
+            )?
+            .map(|store| store.data.balance.0)
+            .unwrap_or_default())
+    }
+
+    // FA 元数据, 存放在 metadata object 地址
+    pub fn metadata(
+        &self,
+        metadata: AccountAddress,
+    ) -> Result<FungibleAssetMetadata, anyhow::Error> {
+        Ok(self
+            .client
+            .account_resource::<FungibleAssetMetadata>(metadata, "0x1::fungible_asset::Metadata")?
+            .data)
+    }
+
+    // 转账到 receiver 的 primary store, 返回交易 hash
+    pub fn transfer(
+        &self,
+        sender: LocalAccount,
+        metadata: AccountAddress,
+        receiver: AccountAddress,
+        amount: u64,
+    ) -> Result<String, anyhow::Error> {
+        self.client.submit_entry_function(
+            sender,
+            ModuleId::new(
+                AccountAddress::ONE,
+                Identifier::new("primary_fungible_store")?,
+            ),
+            "transfer",
+            vec![struct_type_tag(
+                AccountAddress::ONE,
+                "fungible_asset",
+                "Metadata",
+                vec![],
+            )],
+            vec![
+                bcs::to_bytes(&metadata)?,
+                bcs::to_bytes(&receiver)?,
+                bcs::to_bytes(&amount)?,
+            ],
+        )
+    }
+}
+
+/// Address of the primary fungible store of `owner` for the asset `metadata`
+pub fn primary_store_address(owner: &AccountAddress, metadata: &AccountAddress) -> AccountAddress {
+    create_user_derived_object_address(owner, metadata)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FungibleAssetMetadata {
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+    pub icon_uri: String,
+    pub project_uri: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FungibleStore {
+    pub metadata: ObjectRef,
+    pub balance: U64,
+    pub frozen: bool,
+}
@@ -1,21 +1,59 @@
 pub mod types;
 
 mod client;
+mod codegen;
 mod coin;
+mod digital_asset;
+mod entry_function;
 mod faucet;
+mod fungible_asset;
 mod module;
+mod multi_agent;
+mod multi_ed25519;
+mod object;
+mod property_map;
+mod script;
+mod sponsor;
 mod token;
+mod type_tag;
 
-pub use crate::client::Client;
-pub use crate::coin::CoinClient;
+pub use crate::client::{Client, Event, MoveModule, Table, Transaction, TransactionEvent};
+pub use crate::codegen::{
+    generate_bindings, generate_bindings_from_abi_file, generate_bindings_from_package,
+};
+pub use crate::coin::{aptos_coin_type, format_amount, CoinActivity, CoinClient, CoinInfo};
+pub use crate::digital_asset::{
+    collection_address, minted_tokens, token_address, DigitalAsset, DigitalAssetClient,
+    DigitalAssetCollection, DigitalAssetCollectionOptions,
+};
+pub use crate::entry_function::{EntryFunctionCall, MoveArgument};
 pub use crate::faucet::FaucetClient;
-pub use crate::module::ModuleClient;
-pub use crate::token::TokenClient;
+pub use crate::fungible_asset::{
+    primary_store_address, FungibleAssetClient, FungibleAssetMetadata,
+};
+pub use crate::module::{CompiledPackage, ModuleClient};
+pub use crate::multi_agent::MultiAgentTransaction;
+pub use crate::multi_ed25519::{MultiEd25519Account, PartialSignature};
+pub use crate::object::{create_object_address, create_user_derived_object_address};
+pub use crate::property_map::{PropertyMap, PropertyValue};
+pub use crate::script::ScriptCall;
+#[cfg(not(target_arch = "wasm32"))]
+pub use crate::sponsor::request_sponsor_signature;
+pub use crate::sponsor::{Sponsor, SponsorPolicy, SPONSOR_PATH};
+pub use crate::token::{
+    CollectionDescriptionMutateEvent, CollectionOptions, CollectionUriMutateEvent,
+    DescriptionMutateEvent, OwnedToken, PendingOffer, RoyaltyMutateEvent, TokenClient,
+    TokenOptions, UriMutationEvent,
+};
+pub use crate::type_tag::{format_struct_tag, format_type_tag, IntoTypeTag};
 
+pub use anyhow;
 pub use aptos_crypto;
 pub use aptos_types;
 pub use bcs;
 pub use move_core_types;
+pub use serde;
+pub use serde_json;
 
 #[cfg(test)]
 mod tests {
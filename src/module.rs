@@ -2,12 +2,18 @@ use aptos_types::{
     account_address::AccountAddress,
     transaction::{EntryFunction, TransactionPayload},
 };
+use move_binary_format::{access::ModuleAccess, CompiledModule};
 use move_core_types::{
     identifier::Identifier,
     language_storage::{ModuleId, TypeTag},
 };
+use std::{collections::HashMap, fs, path::Path};
 
-use crate::{client::Client, types::LocalAccount};
+use crate::{
+    client::Client,
+    entry_function::{EntryFunctionCall, MoveArgument},
+    types::LocalAccount,
+};
 
 #[derive(Debug)]
 pub struct ModuleClient {
@@ -42,4 +48,222 @@ impl ModuleClient {
             .create_single_signer_bcs_transaction(account, payload);
         Ok(self.client.submit_bcs_transaction(signed_transaction)?)
     }
+
+    // 按模块 ABI 将 JSON 参数转换为 BCS 参数
+    pub fn entry_function_json(
+        &self,
+        account: LocalAccount,
+        name: &str,
+        function: &str,
+        ty_args: Vec<TypeTag>,
+        args: Vec<serde_json::Value>,
+    ) -> Result<String, anyhow::Error> {
+        let abi = self
+            .client
+            .account_module(self.address, name)?
+            .abi
+            .ok_or_else(|| anyhow::anyhow!("module {} has no ABI", name))?;
+        let payload = EntryFunctionCall::from_json(&abi, function, ty_args, args)?.payload()?;
+        let signed_transaction = self
+            .client
+            .create_single_signer_bcs_transaction(account, payload);
+        self.client.submit_bcs_transaction(signed_transaction)
+    }
+
+    // 0x1::code::publish_package_txn, 模块按依赖顺序传入
+    pub fn publish_package(
+        &self,
+        account: LocalAccount,
+        metadata: Vec<u8>,
+        modules: Vec<Vec<u8>>,
+    ) -> Result<String, anyhow::Error> {
+        let call = EntryFunctionCall::new(
+            ModuleId::new(
+                AccountAddress::from_hex_literal("0x1")?,
+                Identifier::new("code")?,
+            ),
+            "publish_package_txn",
+            vec![],
+            vec![MoveArgument::Bytes(metadata), MoveArgument::vector(modules)],
+        );
+        let signed_transaction = self
+            .client
+            .create_single_signer_bcs_transaction(account, call.payload()?);
+        self.client.submit_bcs_transaction(signed_transaction)
+    }
+
+    // 发布 build/<package> 目录中的编译结果
+    pub fn publish_compiled_package(
+        &self,
+        account: LocalAccount,
+        package: CompiledPackage,
+    ) -> Result<String, anyhow::Error> {
+        self.publish_package(account, package.metadata, package.modules)
+    }
+}
+
+/// A compiled Move package, read from `build/<package>` of `aptos move compile --save-metadata`
+#[derive(Debug, Clone)]
+pub struct CompiledPackage {
+    pub metadata: Vec<u8>,
+    pub modules: Vec<Vec<u8>>,
+}
+
+impl CompiledPackage {
+    /// Read `package-metadata.bcs` and `bytecode_modules/*.mv`, modules sorted by dependency
+    pub fn read<P: AsRef<Path>>(package_dir: P) -> Result<Self, anyhow::Error> {
+        let package_dir = package_dir.as_ref();
+        let metadata_path = package_dir.join("package-metadata.bcs");
+        let metadata = fs::read(&metadata_path).map_err(|e| {
+            anyhow::anyhow!(
+                "failed to read {}, compile with --save-metadata: {}",
+                metadata_path.display(),
+                e
+            )
+        })?;
+        let modules = read_bytecode_modules(package_dir)?
+            .into_iter()
+            .map(|(bytes, _)| bytes)
+            .collect();
+        Ok(Self { metadata, modules })
+    }
+}
+
+// 读取 bytecode_modules/*.mv, 被依赖的模块在前
+pub(crate) fn read_bytecode_modules(
+    package_dir: &Path,
+) -> Result<Vec<(Vec<u8>, CompiledModule)>, anyhow::Error> {
+    let dir = package_dir.join("bytecode_modules");
+    let mut paths = vec![];
+    for entry in fs::read_dir(&dir)
+        .map_err(|e| anyhow::anyhow!("failed to read {}: {}", dir.display(), e))?
+    {
+        let path = entry?.path();
+        if path.extension().map_or(false, |ext| ext == "mv") {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+    let mut modules = HashMap::new();
+    let mut ids = vec![];
+    for path in paths {
+        let bytes = fs::read(&path)?;
+        let module = CompiledModule::deserialize(&bytes)
+            .map_err(|e| anyhow::anyhow!("invalid module {}: {:?}", path.display(), e))?;
+        ids.push(module.self_id());
+        modules.insert(module.self_id(), (bytes, module));
+    }
+    let mut sorted = vec![];
+    for id in &ids {
+        visit_module(id, &mut modules, &mut sorted);
+    }
+    Ok(sorted)
+}
+
+fn visit_module(
+    id: &ModuleId,
+    modules: &mut HashMap<ModuleId, (Vec<u8>, CompiledModule)>,
+    sorted: &mut Vec<(Vec<u8>, CompiledModule)>,
+) {
+    // 移除后再访问依赖, 已访问的模块不会重复加入
+    if let Some((bytes, module)) = modules.remove(id) {
+        for dependency in module.immediate_dependencies() {
+            visit_module(&dependency, modules, sorted);
+        }
+        sorted.push((bytes, module));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use move_binary_format::file_format::{
+        empty_module, AddressIdentifierIndex, IdentifierIndex, ModuleHandle,
+    };
+    use std::path::PathBuf;
+
+    // 名为 name 的模块, 依赖同一地址下的 dependencies
+    fn module_bytes(name: &str, dependencies: &[&str]) -> Vec<u8> {
+        let mut module = empty_module();
+        module.address_identifiers = vec![AccountAddress::from_hex_literal("0xcafe").unwrap()];
+        module.identifiers = vec![Identifier::new(name).unwrap()];
+        for dependency in dependencies {
+            module.module_handles.push(ModuleHandle {
+                address: AddressIdentifierIndex(0),
+                name: IdentifierIndex(module.identifiers.len() as u16),
+            });
+            module
+                .identifiers
+                .push(Identifier::new(*dependency).unwrap());
+        }
+        let mut bytes = vec![];
+        module.serialize(&mut bytes).unwrap();
+        bytes
+    }
+
+    fn package_dir(name: &str, modules: &[(&str, &[&str])]) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("aptos-client-{}-{}", name, std::process::id()));
+        let bytecode_dir = dir.join("bytecode_modules");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&bytecode_dir).unwrap();
+        for (module, dependencies) in modules {
+            fs::write(
+                bytecode_dir.join(format!("{}.mv", module)),
+                module_bytes(module, dependencies),
+            )
+            .unwrap();
+        }
+        fs::write(dir.join("package-metadata.bcs"), b"metadata").unwrap();
+        dir
+    }
+
+    fn names(modules: &[(Vec<u8>, CompiledModule)]) -> Vec<String> {
+        modules
+            .iter()
+            .map(|(_, module)| module.self_id().name().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn dependencies_first() {
+        // a_market 依赖 b_coin, 按文件名 a_market 在前
+        let dir = package_dir(
+            "dependencies-first",
+            &[
+                ("a_market", &["b_coin", "c_math"]),
+                ("b_coin", &["c_math"]),
+                ("c_math", &[]),
+                ("d_app", &["a_market"]),
+            ],
+        );
+        let modules = read_bytecode_modules(&dir).unwrap();
+        assert_eq!(
+            names(&modules),
+            vec!["c_math", "b_coin", "a_market", "d_app"]
+        );
+
+        let package = CompiledPackage::read(&dir).unwrap();
+        assert_eq!(package.metadata, b"metadata");
+        assert_eq!(
+            package.modules,
+            modules
+                .into_iter()
+                .map(|(bytes, _)| bytes)
+                .collect::<Vec<_>>()
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn dependencies_outside_package_are_skipped() {
+        // 0x1 等包外的依赖不在 bytecode_modules 中
+        let dir = package_dir(
+            "outside-dependencies",
+            &[("a_user", &["b_base", "missing"]), ("b_base", &[])],
+        );
+        let modules = read_bytecode_modules(&dir).unwrap();
+        assert_eq!(names(&modules), vec!["b_base", "a_user"]);
+        fs::remove_dir_all(dir).unwrap();
+    }
 }
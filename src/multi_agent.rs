@@ -0,0 +1,327 @@
+use aptos_types::{
+    account_address::AccountAddress,
+    transaction::{
+        authenticator::AccountAuthenticator, RawTransaction, RawTransactionWithData,
+        SignedTransaction,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+use crate::types::LocalAccount;
+
+/// A multi-agent transaction collecting the signatures of its sender, secondary signers and
+/// optional fee payer
+///
+/// It can be serialized (BCS or JSON) and sent to signers on other machines, who return the
+/// `AccountAuthenticator` from `sign`, added back with `add_authenticator`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MultiAgentTransaction {
+    raw_transaction: RawTransaction,
+    secondary_signers: Vec<AccountAddress>,
+    fee_payer: Option<AccountAddress>,
+    sender_authenticator: Option<AccountAuthenticator>,
+    secondary_authenticators: Vec<Option<AccountAuthenticator>>,
+    fee_payer_authenticator: Option<AccountAuthenticator>,
+}
+
+impl MultiAgentTransaction {
+    pub fn new(raw_transaction: RawTransaction, secondary_signers: Vec<AccountAddress>) -> Self {
+        let secondary_authenticators = vec![None; secondary_signers.len()];
+        Self {
+            raw_transaction,
+            secondary_signers,
+            fee_payer: None,
+            sender_authenticator: None,
+            secondary_authenticators,
+            fee_payer_authenticator: None,
+        }
+    }
+
+    /// A transaction whose gas is paid by `fee_payer` instead of the sender
+    pub fn with_fee_payer(
+        raw_transaction: RawTransaction,
+        secondary_signers: Vec<AccountAddress>,
+        fee_payer: AccountAddress,
+    ) -> Self {
+        Self {
+            fee_payer: Some(fee_payer),
+            ..Self::new(raw_transaction, secondary_signers)
+        }
+    }
+
+    pub fn raw_transaction(&self) -> &RawTransaction {
+        &self.raw_transaction
+    }
+
+    pub fn sender(&self) -> AccountAddress {
+        self.raw_transaction.sender()
+    }
+
+    pub fn secondary_signers(&self) -> &[AccountAddress] {
+        &self.secondary_signers
+    }
+
+    pub fn fee_payer(&self) -> Option<AccountAddress> {
+        self.fee_payer
+    }
+
+    /// The message every signer signs
+    pub fn signing_message(&self) -> RawTransactionWithData {
+        match self.fee_payer {
+            Some(fee_payer) => RawTransactionWithData::new_fee_payer(
+                self.raw_transaction.clone(),
+                self.secondary_signers.clone(),
+                fee_payer,
+            ),
+            None => RawTransactionWithData::new_multi_agent(
+                self.raw_transaction.clone(),
+                self.secondary_signers.clone(),
+            ),
+        }
+    }
+
+    /// Sign as `account`, without adding the signature, e.g. on a remote signer
+    pub fn sign(&self, account: &LocalAccount) -> Result<AccountAuthenticator, anyhow::Error> {
+        self.signer_index(account.address())?;
+        account.sign_message(&self.signing_message())
+    }
+
+    /// Sign as `account` and add the signature
+    pub fn sign_and_add(&mut self, account: &LocalAccount) -> Result<(), anyhow::Error> {
+        let authenticator = self.sign(account)?;
+        self.add_authenticator(account.address(), authenticator)
+    }
+
+    /// Add the signature of `signer`, checked against the signing message
+    pub fn add_authenticator(
+        &mut self,
+        signer: AccountAddress,
+        authenticator: AccountAuthenticator,
+    ) -> Result<(), anyhow::Error> {
+        authenticator
+            .verify(&self.signing_message())
+            .map_err(|e| anyhow::anyhow!("invalid signature of {}: {}", signer, e))?;
+        match self.signer_index(signer)? {
+            Signer::Sender => self.sender_authenticator = Some(authenticator),
+            Signer::Secondary(i) => self.secondary_authenticators[i] = Some(authenticator),
+            Signer::FeePayer => self.fee_payer_authenticator = Some(authenticator),
+        }
+        Ok(())
+    }
+
+    /// Signers whose signature has not been added yet, sender first and fee payer last
+    pub fn missing_signers(&self) -> Vec<AccountAddress> {
+        let mut missing = vec![];
+        if self.sender_authenticator.is_none() {
+            missing.push(self.sender());
+        }
+        for (signer, authenticator) in self
+            .secondary_signers
+            .iter()
+            .zip(&self.secondary_authenticators)
+        {
+            if authenticator.is_none() {
+                missing.push(*signer);
+            }
+        }
+        if let (Some(fee_payer), None) = (self.fee_payer, &self.fee_payer_authenticator) {
+            missing.push(fee_payer);
+        }
+        missing
+    }
+
+    /// Assemble the signed transaction once every signer has signed
+    pub fn into_signed_transaction(self) -> Result<SignedTransaction, anyhow::Error> {
+        let missing = self.missing_signers();
+        if !missing.is_empty() {
+            return Err(anyhow::anyhow!("missing signatures of {:?}", missing));
+        }
+        let sender_authenticator = self.sender_authenticator.unwrap();
+        let secondary_authenticators = self
+            .secondary_authenticators
+            .into_iter()
+            .flatten()
+            .collect();
+        Ok(match (self.fee_payer, self.fee_payer_authenticator) {
+            (Some(fee_payer), Some(fee_payer_authenticator)) => SignedTransaction::new_fee_payer(
+                self.raw_transaction,
+                sender_authenticator,
+                self.secondary_signers,
+                secondary_authenticators,
+                fee_payer,
+                fee_payer_authenticator,
+            ),
+            _ => SignedTransaction::new_multi_agent(
+                self.raw_transaction,
+                sender_authenticator,
+                self.secondary_signers,
+                secondary_authenticators,
+            ),
+        })
+    }
+
+    fn signer_index(&self, signer: AccountAddress) -> Result<Signer, anyhow::Error> {
+        if signer == self.sender() {
+            return Ok(Signer::Sender);
+        }
+        if let Some(i) = self.secondary_signers.iter().position(|s| *s == signer) {
+            return Ok(Signer::Secondary(i));
+        }
+        match self.fee_payer {
+            Some(fee_payer) if fee_payer == signer => Ok(Signer::FeePayer),
+            _ => Err(anyhow::anyhow!(
+                "{} is not a signer of the transaction",
+                signer
+            )),
+        }
+    }
+}
+
+// 签名者在交易中的位置
+enum Signer {
+    Sender,
+    Secondary(usize),
+    FeePayer,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_crypto::ed25519::{Ed25519PrivateKey, Ed25519PublicKey};
+    use aptos_types::{
+        chain_id::ChainId,
+        transaction::{authenticator::AuthenticationKey, Script, TransactionPayload},
+    };
+
+    fn local_account(seed: u8) -> LocalAccount {
+        let private_key = Ed25519PrivateKey::try_from(&[seed; 32][..]).unwrap();
+        let address =
+            AuthenticationKey::ed25519(&Ed25519PublicKey::from(&private_key)).derived_address();
+        LocalAccount::new(address, private_key, 0)
+    }
+
+    fn raw_transaction(sender: AccountAddress, sequence_number: u64) -> RawTransaction {
+        RawTransaction::new(
+            sender,
+            sequence_number,
+            TransactionPayload::Script(Script::new(vec![], vec![], vec![])),
+            1_000,
+            100,
+            u64::MAX,
+            ChainId::test(),
+        )
+    }
+
+    #[test]
+    fn sender_and_secondary_sign() {
+        let sender = local_account(1);
+        let secondary = local_account(2);
+        let mut transaction = MultiAgentTransaction::new(
+            raw_transaction(sender.address(), 0),
+            vec![secondary.address()],
+        );
+        transaction.sign_and_add(&secondary).unwrap();
+        transaction.sign_and_add(&sender).unwrap();
+        let signed = transaction.into_signed_transaction().unwrap();
+        assert!(signed.verify_signature().is_ok());
+    }
+
+    #[test]
+    fn signature_of_other_message_is_rejected() {
+        let sender = local_account(1);
+        let secondary = local_account(2);
+        let mut transaction = MultiAgentTransaction::new(
+            raw_transaction(sender.address(), 0),
+            vec![secondary.address()],
+        );
+        let other = MultiAgentTransaction::new(
+            raw_transaction(sender.address(), 1),
+            vec![secondary.address()],
+        );
+        let authenticator = other.sign(&secondary).unwrap();
+        assert!(transaction
+            .add_authenticator(secondary.address(), authenticator)
+            .is_err());
+        assert_eq!(
+            transaction.missing_signers(),
+            vec![sender.address(), secondary.address()]
+        );
+    }
+
+    #[test]
+    fn non_signer_is_rejected() {
+        let sender = local_account(1);
+        let stranger = local_account(3);
+        let mut transaction =
+            MultiAgentTransaction::new(raw_transaction(sender.address(), 0), vec![]);
+        assert!(transaction.sign(&stranger).is_err());
+        // 签名本身有效, 但签名者不在交易中
+        let authenticator = stranger
+            .sign_message(&transaction.signing_message())
+            .unwrap();
+        assert!(transaction
+            .add_authenticator(stranger.address(), authenticator)
+            .is_err());
+    }
+
+    #[test]
+    fn missing_signers_order() {
+        let sender = local_account(1);
+        let first = local_account(2);
+        let second = local_account(3);
+        let fee_payer = local_account(4);
+        let mut transaction = MultiAgentTransaction::with_fee_payer(
+            raw_transaction(sender.address(), 0),
+            vec![first.address(), second.address()],
+            fee_payer.address(),
+        );
+        assert_eq!(
+            transaction.missing_signers(),
+            vec![
+                sender.address(),
+                first.address(),
+                second.address(),
+                fee_payer.address()
+            ]
+        );
+        transaction.sign_and_add(&first).unwrap();
+        transaction.sign_and_add(&fee_payer).unwrap();
+        assert_eq!(
+            transaction.missing_signers(),
+            vec![sender.address(), second.address()]
+        );
+        assert!(transaction.clone().into_signed_transaction().is_err());
+        transaction.sign_and_add(&sender).unwrap();
+        transaction.sign_and_add(&second).unwrap();
+        assert!(transaction.missing_signers().is_empty());
+        let signed = transaction.into_signed_transaction().unwrap();
+        assert!(signed.verify_signature().is_ok());
+    }
+
+    #[test]
+    fn signatures_collected_on_other_machines() {
+        let sender = local_account(1);
+        let secondary = local_account(2);
+        let mut transaction = MultiAgentTransaction::new(
+            raw_transaction(sender.address(), 0),
+            vec![secondary.address()],
+        );
+        transaction.sign_and_add(&sender).unwrap();
+
+        // secondary signer 收到部分签名的交易并返回自己的签名
+        let received =
+            bcs::from_bytes::<MultiAgentTransaction>(&bcs::to_bytes(&transaction).unwrap())
+                .unwrap();
+        assert_eq!(received.missing_signers(), vec![secondary.address()]);
+        let authenticator = bcs::from_bytes::<AccountAuthenticator>(
+            &bcs::to_bytes(&received.sign(&secondary).unwrap()).unwrap(),
+        )
+        .unwrap();
+
+        transaction
+            .add_authenticator(secondary.address(), authenticator)
+            .unwrap();
+        let signed = transaction.into_signed_transaction().unwrap();
+        assert!(signed.verify_signature().is_ok());
+    }
+}
@@ -0,0 +1,310 @@
+use aptos_crypto::{
+    ed25519::{Ed25519PrivateKey, Ed25519PublicKey, Ed25519Signature},
+    hash::CryptoHash,
+    multi_ed25519::{MultiEd25519PublicKey, MultiEd25519Signature},
+    traits::{Signature, SigningKey},
+};
+use aptos_types::{
+    account_address::AccountAddress,
+    transaction::{
+        authenticator::{AccountAuthenticator, AuthenticationKey},
+        RawTransaction, SignedTransaction,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+/// A k-of-n MultiEd25519 account, e.g. a 2-of-3 treasury
+///
+/// Key holders sign separately with `sign_partial`, possibly on other machines, and any
+/// `threshold` of the partial signatures are combined into the transaction authenticator.
+#[derive(Debug, Clone)]
+pub struct MultiEd25519Account {
+    address: AccountAddress,
+    public_key: MultiEd25519PublicKey,
+}
+
+/// The signature of one key holder of a `MultiEd25519Account`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PartialSignature {
+    /// Position of the signing key in the public keys of the account
+    pub index: u8,
+    pub signature: Ed25519Signature,
+}
+
+impl MultiEd25519Account {
+    /// The account whose address is derived from the public keys and threshold
+    pub fn new(public_keys: Vec<Ed25519PublicKey>, threshold: u8) -> Result<Self, anyhow::Error> {
+        let public_key = MultiEd25519PublicKey::new(public_keys, threshold)?;
+        let address = AuthenticationKey::multi_ed25519(&public_key).derived_address();
+        Ok(Self {
+            address,
+            public_key,
+        })
+    }
+
+    // 已轮换密钥的账户, 地址与公钥不再对应
+    pub fn with_address(
+        address: AccountAddress,
+        public_keys: Vec<Ed25519PublicKey>,
+        threshold: u8,
+    ) -> Result<Self, anyhow::Error> {
+        Ok(Self {
+            address,
+            public_key: MultiEd25519PublicKey::new(public_keys, threshold)?,
+        })
+    }
+
+    pub fn address(&self) -> AccountAddress {
+        self.address
+    }
+
+    pub fn public_key(&self) -> &MultiEd25519PublicKey {
+        &self.public_key
+    }
+
+    pub fn threshold(&self) -> u8 {
+        *self.public_key.threshold()
+    }
+
+    pub fn authentication_key(&self) -> AuthenticationKey {
+        AuthenticationKey::multi_ed25519(&self.public_key)
+    }
+
+    /// Sign a raw transaction with one of the keys of the account
+    pub fn sign_partial(
+        &self,
+        private_key: &Ed25519PrivateKey,
+        txn: &RawTransaction,
+    ) -> Result<PartialSignature, anyhow::Error> {
+        self.sign_message_partial(private_key, txn)
+    }
+
+    /// Sign any message with one of the keys, e.g. a multi-agent `RawTransactionWithData`
+    pub fn sign_message_partial<T: CryptoHash + Serialize>(
+        &self,
+        private_key: &Ed25519PrivateKey,
+        message: &T,
+    ) -> Result<PartialSignature, anyhow::Error> {
+        let public_key = Ed25519PublicKey::from(private_key);
+        let index = self
+            .public_key
+            .public_keys()
+            .iter()
+            .position(|key| *key == public_key)
+            .ok_or_else(|| anyhow::anyhow!("key is not a key of {}", self.address))?;
+        Ok(PartialSignature {
+            index: index as u8,
+            signature: private_key.sign(message)?,
+        })
+    }
+
+    /// Combine at least `threshold` partial signatures into the signed transaction
+    pub fn sign_transaction(
+        &self,
+        txn: RawTransaction,
+        signatures: Vec<PartialSignature>,
+    ) -> Result<SignedTransaction, anyhow::Error> {
+        let signature = self.combine(&txn, signatures)?;
+        Ok(SignedTransaction::new_multisig(
+            txn,
+            self.public_key.clone(),
+            signature,
+        ))
+    }
+
+    /// Combine partial signatures of a message into an authenticator, e.g. to add to a
+    /// `MultiAgentTransaction`
+    pub fn authenticator<T: CryptoHash + Serialize>(
+        &self,
+        message: &T,
+        signatures: Vec<PartialSignature>,
+    ) -> Result<AccountAuthenticator, anyhow::Error> {
+        let signature = self.combine(message, signatures)?;
+        Ok(AccountAuthenticator::multi_ed25519(
+            self.public_key.clone(),
+            signature,
+        ))
+    }
+
+    // 校验每个部分签名, 同一个 key 只保留一个
+    fn combine<T: CryptoHash + Serialize>(
+        &self,
+        message: &T,
+        signatures: Vec<PartialSignature>,
+    ) -> Result<MultiEd25519Signature, anyhow::Error> {
+        let public_keys = self.public_key.public_keys();
+        let mut combined: Vec<(Ed25519Signature, u8)> = vec![];
+        for partial in signatures {
+            let public_key = public_keys.get(partial.index as usize).ok_or_else(|| {
+                anyhow::anyhow!(
+                    "key index {} out of {} keys",
+                    partial.index,
+                    public_keys.len()
+                )
+            })?;
+            partial.signature.verify(message, public_key).map_err(|e| {
+                anyhow::anyhow!("invalid signature of key {}: {}", partial.index, e)
+            })?;
+            if !combined.iter().any(|(_, index)| *index == partial.index) {
+                combined.push((partial.signature, partial.index));
+            }
+        }
+        if combined.len() < self.threshold() as usize {
+            return Err(anyhow::anyhow!(
+                "{} of {} signatures required, got {}",
+                self.threshold(),
+                public_keys.len(),
+                combined.len()
+            ));
+        }
+        // 超过 threshold 的签名不需要
+        combined.truncate(self.threshold() as usize);
+        Ok(MultiEd25519Signature::new(combined)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_types::{
+        chain_id::ChainId,
+        transaction::{Script, TransactionPayload},
+    };
+
+    fn private_keys() -> Vec<Ed25519PrivateKey> {
+        (1..=3u8)
+            .map(|i| Ed25519PrivateKey::try_from(&[i; 32][..]).unwrap())
+            .collect()
+    }
+
+    fn account(threshold: u8) -> MultiEd25519Account {
+        MultiEd25519Account::new(
+            private_keys().iter().map(Ed25519PublicKey::from).collect(),
+            threshold,
+        )
+        .unwrap()
+    }
+
+    fn raw_transaction(sender: AccountAddress) -> RawTransaction {
+        RawTransaction::new(
+            sender,
+            0,
+            TransactionPayload::Script(Script::new(vec![], vec![], vec![])),
+            1_000,
+            100,
+            u64::MAX,
+            ChainId::test(),
+        )
+    }
+
+    #[test]
+    fn two_of_three_signs_transaction() {
+        let account = account(2);
+        let keys = private_keys();
+        let txn = raw_transaction(account.address());
+        let partials = vec![
+            account.sign_partial(&keys[2], &txn).unwrap(),
+            account.sign_partial(&keys[0], &txn).unwrap(),
+        ];
+        assert_eq!(partials[0].index, 2);
+        assert_eq!(partials[1].index, 0);
+        let signed = account.sign_transaction(txn, partials).unwrap();
+        assert!(signed.verify_signature().is_ok());
+    }
+
+    #[test]
+    fn extra_signatures_are_dropped() {
+        let account = account(2);
+        let txn = raw_transaction(account.address());
+        let partials = private_keys()
+            .iter()
+            .map(|key| account.sign_partial(key, &txn).unwrap())
+            .collect();
+        let signed = account.sign_transaction(txn, partials).unwrap();
+        assert!(signed.verify_signature().is_ok());
+    }
+
+    #[test]
+    fn below_threshold_is_rejected() {
+        let account = account(2);
+        let keys = private_keys();
+        let txn = raw_transaction(account.address());
+        let single = account.sign_partial(&keys[1], &txn).unwrap();
+        assert!(account
+            .sign_transaction(txn.clone(), vec![single.clone()])
+            .is_err());
+        // 同一个 key 的重复签名只计一次
+        assert!(account
+            .sign_transaction(txn, vec![single.clone(), single])
+            .is_err());
+    }
+
+    #[test]
+    fn invalid_partials_are_rejected() {
+        let account = account(2);
+        let keys = private_keys();
+        let txn = raw_transaction(account.address());
+        let first = account.sign_partial(&keys[0], &txn).unwrap();
+        let mut out_of_range = account.sign_partial(&keys[1], &txn).unwrap();
+        out_of_range.index = 3;
+        assert!(account
+            .sign_transaction(txn.clone(), vec![first.clone(), out_of_range])
+            .is_err());
+        // 签名与 index 对应的 key 不符
+        let mut wrong_index = account.sign_partial(&keys[1], &txn).unwrap();
+        wrong_index.index = 2;
+        assert!(account
+            .sign_transaction(txn.clone(), vec![first.clone(), wrong_index])
+            .is_err());
+        // 签名的是另一笔交易
+        let other = account
+            .sign_partial(&keys[1], &raw_transaction(AccountAddress::ONE))
+            .unwrap();
+        assert!(account.sign_transaction(txn, vec![first, other]).is_err());
+    }
+
+    #[test]
+    fn foreign_key_cannot_sign() {
+        let account = account(2);
+        let foreign = Ed25519PrivateKey::try_from(&[9u8; 32][..]).unwrap();
+        assert!(account
+            .sign_partial(&foreign, &raw_transaction(account.address()))
+            .is_err());
+    }
+
+    #[test]
+    fn address_from_keys_and_threshold() {
+        let two_of_three = account(2);
+        assert_eq!(
+            two_of_three.address(),
+            two_of_three.authentication_key().derived_address()
+        );
+        assert_ne!(two_of_three.address(), account(3).address());
+        let mut public_keys: Vec<Ed25519PublicKey> =
+            private_keys().iter().map(Ed25519PublicKey::from).collect();
+        public_keys.reverse();
+        assert_ne!(
+            MultiEd25519Account::new(public_keys.clone(), 2)
+                .unwrap()
+                .address(),
+            two_of_three.address()
+        );
+        let rotated =
+            MultiEd25519Account::with_address(AccountAddress::ONE, public_keys, 2).unwrap();
+        assert_eq!(rotated.address(), AccountAddress::ONE);
+        assert!(MultiEd25519Account::new(vec![], 1).is_err());
+    }
+
+    #[test]
+    fn authenticator_for_multi_agent_message() {
+        let account = account(2);
+        let keys = private_keys();
+        let txn = raw_transaction(AccountAddress::ONE);
+        let partials = vec![
+            account.sign_partial(&keys[0], &txn).unwrap(),
+            account.sign_partial(&keys[1], &txn).unwrap(),
+        ];
+        let authenticator = account.authenticator(&txn, partials).unwrap();
+        assert!(authenticator.verify(&txn).is_ok());
+    }
+}
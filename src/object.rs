@@ -0,0 +1,72 @@
+use aptos_crypto::HashValue;
+use aptos_types::account_address::AccountAddress;
+use serde::Deserialize;
+
+// 0x1::object 中的地址派生 scheme
+const OBJECT_DERIVED_SCHEME: u8 = 0xFC;
+const OBJECT_FROM_SEED_ADDRESS_SCHEME: u8 = 0xFE;
+
+/// Address of a named object, same as `0x1::object::create_object_address`
+pub fn create_object_address(source: &AccountAddress, seed: &[u8]) -> AccountAddress {
+    derive_address(source, seed, OBJECT_FROM_SEED_ADDRESS_SCHEME)
+}
+
+/// Address of an object derived from another address, same as
+/// `0x1::object::create_user_derived_object_address`, e.g. a primary fungible store
+pub fn create_user_derived_object_address(
+    source: &AccountAddress,
+    derive_from: &AccountAddress,
+) -> AccountAddress {
+    derive_address(source, derive_from.as_ref(), OBJECT_DERIVED_SCHEME)
+}
+
+fn derive_address(source: &AccountAddress, seed: &[u8], scheme: u8) -> AccountAddress {
+    let mut bytes = source.to_vec();
+    bytes.extend_from_slice(seed);
+    bytes.push(scheme);
+    AccountAddress::new(*HashValue::sha3_256_of(&bytes))
+}
+
+/// `0x1::object::ObjectCore`, stored at every object address
+#[derive(Debug, Deserialize)]
+pub struct ObjectCore {
+    pub owner: AccountAddress,
+    pub allow_ungated_transfer: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn address(s: &str) -> AccountAddress {
+        AccountAddress::from_hex_literal(s).unwrap()
+    }
+
+    #[test]
+    fn object_address_from_seed() {
+        // sha3_256(0x1 || "collection" || 0xFE)
+        assert_eq!(
+            create_object_address(&AccountAddress::ONE, b"collection"),
+            address("0x576f5756b1b00af4e460d6c652061638e4176e9f2986893985aad3a87d938a8f")
+        );
+    }
+
+    #[test]
+    fn user_derived_object_address() {
+        // sha3_256(0xabab..ab || 0xa || 0xFC), e.g. the APT primary store of 0xabab..ab
+        let owner = address("0xabababababababababababababababababababababababababababababababab");
+        assert_eq!(
+            create_user_derived_object_address(&owner, &address("0xa")),
+            address("0xa03e7655f0f3beed3c4831d91db8748a237d4f2e8cd4b4d9186a7f366d7c52d6")
+        );
+    }
+
+    #[test]
+    fn schemes_give_different_addresses() {
+        let seed = address("0xa");
+        assert_ne!(
+            create_object_address(&AccountAddress::ONE, seed.as_ref()),
+            create_user_derived_object_address(&AccountAddress::ONE, &seed)
+        );
+    }
+}
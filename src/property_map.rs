@@ -0,0 +1,198 @@
+use aptos_types::account_address::AccountAddress;
+
+use crate::token::TokenDataPropertyMap;
+
+/// A typed value of `0x3::property_map`, stored on chain as BCS bytes and a type name
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PropertyValue {
+    Bool(bool),
+    U8(u8),
+    U64(u64),
+    U128(u128),
+    Address(AccountAddress),
+    String(String),
+    Bytes(Vec<u8>),
+}
+
+impl PropertyValue {
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Self::Bool(_) => "bool",
+            Self::U8(_) => "u8",
+            Self::U64(_) => "u64",
+            Self::U128(_) => "u128",
+            Self::Address(_) => "address",
+            Self::String(_) => "0x1::string::String",
+            Self::Bytes(_) => "vector<u8>",
+        }
+    }
+
+    pub fn to_bcs(&self) -> Result<Vec<u8>, anyhow::Error> {
+        Ok(match self {
+            Self::Bool(v) => bcs::to_bytes(v)?,
+            Self::U8(v) => bcs::to_bytes(v)?,
+            Self::U64(v) => bcs::to_bytes(v)?,
+            Self::U128(v) => bcs::to_bytes(v)?,
+            Self::Address(v) => bcs::to_bytes(v)?,
+            Self::String(v) => bcs::to_bytes(v)?,
+            Self::Bytes(v) => bcs::to_bytes(v)?,
+        })
+    }
+
+    pub fn from_bcs(type_name: &str, bytes: &[u8]) -> Result<Self, anyhow::Error> {
+        Ok(match type_name {
+            "bool" => Self::Bool(bcs::from_bytes(bytes)?),
+            "u8" => Self::U8(bcs::from_bytes(bytes)?),
+            "u64" => Self::U64(bcs::from_bytes(bytes)?),
+            "u128" => Self::U128(bcs::from_bytes(bytes)?),
+            "address" => Self::Address(bcs::from_bytes(bytes)?),
+            "0x1::string::String" | "string" | "String" => Self::String(bcs::from_bytes(bytes)?),
+            "vector<u8>" => Self::Bytes(bcs::from_bytes(bytes)?),
+            _ => return Err(anyhow::anyhow!("unsupported property type: {}", type_name)),
+        })
+    }
+}
+
+/// Typed token properties in insertion order, see `0x3::property_map::PropertyMap`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PropertyMap {
+    entries: Vec<(String, PropertyValue)>,
+}
+
+impl PropertyMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // 已存在的 key 会被覆盖
+    pub fn insert(&mut self, key: &str, value: PropertyValue) -> &mut Self {
+        match self.entries.iter_mut().find(|(k, _)| k == key) {
+            Some(entry) => entry.1 = value,
+            None => self.entries.push((key.to_string(), value)),
+        }
+        self
+    }
+
+    pub fn get(&self, key: &str) -> Option<&PropertyValue> {
+        self.entries.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &PropertyValue)> {
+        self.entries.iter().map(|(k, v)| (k.as_str(), v))
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Encode as the keys, BCS values and types arguments of the token entry functions
+    pub fn encode(&self) -> Result<(Vec<String>, Vec<Vec<u8>>, Vec<String>), anyhow::Error> {
+        let mut keys = vec![];
+        let mut values = vec![];
+        let mut types = vec![];
+        for (key, value) in &self.entries {
+            keys.push(key.clone());
+            values.push(value.to_bcs()?);
+            types.push(value.type_name().to_string());
+        }
+        Ok((keys, values, types))
+    }
+
+    /// Decode `default_properties` of a `TokenData` or `token_properties` of a `Token`
+    pub fn decode(map: &TokenDataPropertyMap) -> Result<Self, anyhow::Error> {
+        let mut properties = Self::new();
+        for item in &map.map.data {
+            properties.insert(
+                &item.key,
+                PropertyValue::from_bcs(&item.value.value_type, &item.value.value.0)?,
+            );
+        }
+        Ok(properties)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_values_as_bcs() {
+        let mut properties = PropertyMap::new();
+        properties
+            .insert("level", PropertyValue::U64(5))
+            .insert("name", PropertyValue::String("sword".to_string()))
+            .insert("rare", PropertyValue::Bool(true));
+        let (keys, values, types) = properties.encode().unwrap();
+        assert_eq!(keys, vec!["level", "name", "rare"]);
+        assert_eq!(
+            values,
+            vec![
+                vec![5, 0, 0, 0, 0, 0, 0, 0],
+                vec![5, b's', b'w', b'o', b'r', b'd'],
+                vec![1],
+            ]
+        );
+        assert_eq!(types, vec!["u64", "0x1::string::String", "bool"]);
+    }
+
+    #[test]
+    fn insert_overwrites_in_place() {
+        let mut properties = PropertyMap::new();
+        properties
+            .insert("a", PropertyValue::U8(1))
+            .insert("b", PropertyValue::U8(2))
+            .insert("a", PropertyValue::U8(3));
+        assert_eq!(properties.len(), 2);
+        assert_eq!(properties.get("a"), Some(&PropertyValue::U8(3)));
+        assert_eq!(properties.iter().next().unwrap().0, "a");
+    }
+
+    #[test]
+    fn bcs_round_trip() {
+        let values = [
+            PropertyValue::Bool(false),
+            PropertyValue::U8(255),
+            PropertyValue::U64(u64::MAX),
+            PropertyValue::U128(u128::MAX),
+            PropertyValue::Address(AccountAddress::ONE),
+            PropertyValue::String("héllo".to_string()),
+            PropertyValue::Bytes(vec![0xde, 0xad]),
+        ];
+        for value in values {
+            let bytes = value.to_bcs().unwrap();
+            assert_eq!(
+                PropertyValue::from_bcs(value.type_name(), &bytes).unwrap(),
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn from_bcs_rejects_unknown_type() {
+        assert!(PropertyValue::from_bcs("u16", &[1, 0]).is_err());
+    }
+
+    #[test]
+    fn decode_node_json() {
+        // token_properties as returned by the REST API
+        let map: TokenDataPropertyMap = serde_json::from_value(serde_json::json!({
+            "map": {
+                "data": [
+                    {"key": "level", "value": {"type": "u64", "value": "0x0500000000000000"}},
+                    {"key": "name", "value": {"type": "0x1::string::String", "value": "0x0573776f7264"}},
+                ]
+            }
+        }))
+        .unwrap();
+        let properties = PropertyMap::decode(&map).unwrap();
+        assert_eq!(properties.get("level"), Some(&PropertyValue::U64(5)));
+        assert_eq!(
+            properties.get("name"),
+            Some(&PropertyValue::String("sword".to_string()))
+        );
+    }
+}
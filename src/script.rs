@@ -0,0 +1,182 @@
+use aptos_types::transaction::{Script, TransactionArgument, TransactionPayload};
+use move_binary_format::{access::ScriptAccess, file_format::SignatureToken, CompiledScript};
+use move_core_types::language_storage::TypeTag;
+use std::{fs, path::Path};
+
+/// A compiled Move script with its type and value arguments
+#[derive(Debug, Clone)]
+pub struct ScriptCall {
+    pub code: Vec<u8>,
+    pub ty_args: Vec<TypeTag>,
+    pub args: Vec<TransactionArgument>,
+}
+
+impl ScriptCall {
+    pub fn new(code: Vec<u8>, ty_args: Vec<TypeTag>, args: Vec<TransactionArgument>) -> Self {
+        Self {
+            code,
+            ty_args,
+            args,
+        }
+    }
+
+    /// Load a compiled script, e.g. `build/<package>/bytecode_scripts/main.mv`
+    ///
+    /// The bytecode is checked to be a script taking as many non-signer arguments as given
+    pub fn from_file<P: AsRef<Path>>(
+        path: P,
+        ty_args: Vec<TypeTag>,
+        args: Vec<TransactionArgument>,
+    ) -> Result<Self, anyhow::Error> {
+        let path = path.as_ref();
+        let code = fs::read(path)
+            .map_err(|e| anyhow::anyhow!("failed to read {}: {}", path.display(), e))?;
+        let script = CompiledScript::deserialize(&code)
+            .map_err(|e| anyhow::anyhow!("invalid script {}: {:?}", path.display(), e))?;
+        if script.type_parameters.len() != ty_args.len() {
+            return Err(anyhow::anyhow!(
+                "script {} expects {} type arguments, got {}",
+                path.display(),
+                script.type_parameters.len(),
+                ty_args.len()
+            ));
+        }
+        // 开头的 signer 由交易签名者提供
+        let params = &script.signature_at(script.parameters).0;
+        let signers = params
+            .iter()
+            .take_while(|token| match token {
+                SignatureToken::Signer => true,
+                SignatureToken::Reference(inner) => **inner == SignatureToken::Signer,
+                _ => false,
+            })
+            .count();
+        if params.len() - signers != args.len() {
+            return Err(anyhow::anyhow!(
+                "script {} expects {} arguments, got {}",
+                path.display(),
+                params.len() - signers,
+                args.len()
+            ));
+        }
+        Ok(Self::new(code, ty_args, args))
+    }
+
+    pub fn payload(&self) -> TransactionPayload {
+        TransactionPayload::Script(Script::new(
+            self.code.clone(),
+            self.ty_args.clone(),
+            self.args.clone(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use move_binary_format::file_format::{empty_script, AbilitySet, Signature, SignatureIndex};
+    use move_core_types::account_address::AccountAddress;
+    use std::path::PathBuf;
+
+    // 编译后的脚本, 参数为 params, 带 type_parameters 个类型参数
+    fn script_file(name: &str, params: Vec<SignatureToken>, type_parameters: usize) -> PathBuf {
+        let mut script = empty_script();
+        script.signatures = vec![Signature(vec![]), Signature(params)];
+        script.parameters = SignatureIndex(1);
+        script.type_parameters = vec![AbilitySet::EMPTY; type_parameters];
+        let mut bytes = vec![];
+        script.serialize(&mut bytes).unwrap();
+        let path =
+            std::env::temp_dir().join(format!("aptos-client-{}-{}.mv", name, std::process::id()));
+        fs::write(&path, bytes).unwrap();
+        path
+    }
+
+    #[test]
+    fn leading_signers_are_skipped() {
+        let path = script_file(
+            "leading-signers",
+            vec![
+                SignatureToken::Reference(Box::new(SignatureToken::Signer)),
+                SignatureToken::Signer,
+                SignatureToken::Address,
+                SignatureToken::U64,
+            ],
+            1,
+        );
+        let call = ScriptCall::from_file(
+            &path,
+            vec![TypeTag::U8],
+            vec![
+                TransactionArgument::Address(AccountAddress::ONE),
+                TransactionArgument::U64(1),
+            ],
+        )
+        .unwrap();
+        assert_eq!(call.code, fs::read(&path).unwrap());
+        assert!(matches!(call.payload(), TransactionPayload::Script(_)));
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn argument_count_mismatch() {
+        let path = script_file(
+            "argument-count",
+            vec![
+                SignatureToken::Reference(Box::new(SignatureToken::Signer)),
+                SignatureToken::U64,
+            ],
+            0,
+        );
+        assert!(ScriptCall::from_file(&path, vec![], vec![]).is_err());
+        assert!(ScriptCall::from_file(
+            &path,
+            vec![],
+            vec![TransactionArgument::U64(1), TransactionArgument::U64(2)]
+        )
+        .is_err());
+        // 签名者参数不由 args 提供
+        assert!(ScriptCall::from_file(
+            &path,
+            vec![],
+            vec![
+                TransactionArgument::Address(AccountAddress::ONE),
+                TransactionArgument::U64(1)
+            ]
+        )
+        .is_err());
+        assert!(ScriptCall::from_file(&path, vec![], vec![TransactionArgument::U64(1)]).is_ok());
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn signer_after_other_arguments_is_an_argument() {
+        let path = script_file(
+            "trailing-signer",
+            vec![SignatureToken::U64, SignatureToken::Signer],
+            0,
+        );
+        assert!(ScriptCall::from_file(&path, vec![], vec![TransactionArgument::U64(1)]).is_err());
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn type_argument_count_mismatch() {
+        let path = script_file("type-arguments", vec![SignatureToken::Signer], 2);
+        assert!(ScriptCall::from_file(&path, vec![], vec![]).is_err());
+        assert!(ScriptCall::from_file(&path, vec![TypeTag::U8], vec![]).is_err());
+        assert!(ScriptCall::from_file(&path, vec![TypeTag::U8, TypeTag::U64], vec![]).is_ok());
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn invalid_bytecode() {
+        let path = std::env::temp_dir().join(format!(
+            "aptos-client-invalid-script-{}.mv",
+            std::process::id()
+        ));
+        fs::write(&path, b"not a script").unwrap();
+        assert!(ScriptCall::from_file(&path, vec![], vec![]).is_err());
+        fs::remove_file(path).unwrap();
+    }
+}
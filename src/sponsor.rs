@@ -0,0 +1,572 @@
+use aptos_types::{
+    account_address::AccountAddress,
+    chain_id::ChainId,
+    transaction::{authenticator::AccountAuthenticator, RawTransaction, TransactionPayload},
+};
+#[cfg(not(target_arch = "wasm32"))]
+use std::{
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream, ToSocketAddrs},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{multi_agent::MultiAgentTransaction, types::LocalAccount};
+
+/// Path `Sponsor::serve` handles, other paths get 404
+pub const SPONSOR_PATH: &str = "/sponsor";
+
+// 请求头与请求体上限, 防止恶意请求占用内存
+#[cfg(not(target_arch = "wasm32"))]
+const MAX_HEADER_SIZE: u64 = 8 * 1024;
+#[cfg(not(target_arch = "wasm32"))]
+const MAX_REQUEST_SIZE: usize = 64 * 1024;
+// 同时处理的连接数, 单个慢连接最多占用一个 worker 直到超时
+#[cfg(not(target_arch = "wasm32"))]
+const SERVE_WORKERS: usize = 8;
+#[cfg(not(target_arch = "wasm32"))]
+const IO_TIMEOUT: Duration = Duration::from_secs(5);
+// accept 失败后的等待时间, 避免 EMFILE 等持续错误时空转
+#[cfg(not(target_arch = "wasm32"))]
+const ACCEPT_BACKOFF: Duration = Duration::from_millis(100);
+
+/// Checks a sponsor applies before paying the gas of a transaction
+#[derive(Debug, Clone)]
+pub struct SponsorPolicy {
+    /// Chain the sponsor pays on, transactions for other chains are rejected
+    pub chain_id: ChainId,
+    /// Entry functions that may be sponsored, e.g. `0x1::aptos_account::transfer`, empty allows any
+    pub allowed_functions: Vec<String>,
+    /// Senders that may be sponsored, empty allows any
+    pub allowed_senders: Vec<AccountAddress>,
+    pub max_gas_amount: u64,
+    pub max_gas_unit_price: u64,
+    /// Whether script payloads may be sponsored, they can call any function
+    pub allow_scripts: bool,
+}
+
+impl SponsorPolicy {
+    /// A policy for `chain_id` with the given gas limits and no scripts
+    ///
+    /// It still sponsors ANY sender calling ANY entry function, restrict it with
+    /// `allowed_functions` and `allowed_senders` before serving it publicly.
+    pub fn new(chain_id: ChainId, max_gas_amount: u64, max_gas_unit_price: u64) -> Self {
+        Self {
+            chain_id,
+            allowed_functions: vec![],
+            allowed_senders: vec![],
+            max_gas_amount,
+            max_gas_unit_price,
+            allow_scripts: false,
+        }
+    }
+
+    pub fn check(&self, raw_transaction: &RawTransaction) -> Result<(), anyhow::Error> {
+        if raw_transaction.chain_id() != self.chain_id {
+            return Err(anyhow::anyhow!(
+                "chain id {} is not sponsored",
+                raw_transaction.chain_id()
+            ));
+        }
+        if !self.allowed_senders.is_empty()
+            && !self.allowed_senders.contains(&raw_transaction.sender())
+        {
+            return Err(anyhow::anyhow!(
+                "sender {} is not sponsored",
+                raw_transaction.sender()
+            ));
+        }
+        if raw_transaction.max_gas_amount() > self.max_gas_amount {
+            return Err(anyhow::anyhow!(
+                "max gas amount {} exceeds {}",
+                raw_transaction.max_gas_amount(),
+                self.max_gas_amount
+            ));
+        }
+        if raw_transaction.gas_unit_price() > self.max_gas_unit_price {
+            return Err(anyhow::anyhow!(
+                "gas unit price {} exceeds {}",
+                raw_transaction.gas_unit_price(),
+                self.max_gas_unit_price
+            ));
+        }
+        match raw_transaction.payload() {
+            TransactionPayload::EntryFunction(entry_function) => {
+                let function = format!(
+                    "{}::{}::{}",
+                    entry_function.module().address().to_hex_literal(),
+                    entry_function.module().name(),
+                    entry_function.function()
+                );
+                if !self.allowed_functions.is_empty() && !self.allowed_functions.contains(&function)
+                {
+                    return Err(anyhow::anyhow!("function {} is not sponsored", function));
+                }
+                Ok(())
+            }
+            TransactionPayload::Script(_) if self.allow_scripts => Ok(()),
+            _ => Err(anyhow::anyhow!("payload is not sponsored")),
+        }
+    }
+}
+
+/// A fee payer account co-signing transactions that pass its policy
+#[derive(Debug)]
+pub struct Sponsor {
+    account: LocalAccount,
+    policy: SponsorPolicy,
+}
+
+impl Sponsor {
+    pub fn new(account: LocalAccount, policy: SponsorPolicy) -> Self {
+        Self { account, policy }
+    }
+
+    pub fn address(&self) -> AccountAddress {
+        self.account.address()
+    }
+
+    pub fn policy(&self) -> &SponsorPolicy {
+        &self.policy
+    }
+
+    /// Check the transaction and return the fee payer signature
+    ///
+    /// Transactions where the sponsor is also the sender or a secondary signer are rejected,
+    /// every signer signs the same message so the fee payer signature would authorize them too.
+    pub fn sign(
+        &self,
+        transaction: &MultiAgentTransaction,
+    ) -> Result<AccountAuthenticator, anyhow::Error> {
+        if transaction.fee_payer() != Some(self.address()) {
+            return Err(anyhow::anyhow!(
+                "fee payer of the transaction is not {}",
+                self.address()
+            ));
+        }
+        if transaction.sender() == self.address()
+            || transaction.secondary_signers().contains(&self.address())
+        {
+            return Err(anyhow::anyhow!(
+                "{} only signs as fee payer",
+                self.address()
+            ));
+        }
+        self.policy.check(transaction.raw_transaction())?;
+        transaction.sign(&self.account)
+    }
+
+    /// Check the transaction and add the fee payer signature
+    pub fn sponsor(&self, transaction: &mut MultiAgentTransaction) -> Result<(), anyhow::Error> {
+        let authenticator = self.sign(transaction)?;
+        transaction.add_authenticator(self.address(), authenticator)
+    }
+
+    /// Handle a BCS encoded `MultiAgentTransaction`, returning the BCS encoded fee payer
+    /// `AccountAuthenticator`
+    pub fn handle_request(&self, body: &[u8]) -> Result<Vec<u8>, anyhow::Error> {
+        let transaction = bcs::from_bytes::<MultiAgentTransaction>(body)?;
+        Ok(bcs::to_bytes(&self.sign(&transaction)?)?)
+    }
+
+    /// Serve `handle_request` over HTTP at `POST /sponsor`, other paths get 404
+    ///
+    /// A few connections are handled at once, each request must be read within 5 seconds and
+    /// writes time out after 5 seconds. Clients call `request_sponsor_signature` with
+    /// `http://<addr>/sponsor`.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn serve<A: ToSocketAddrs>(&self, addr: A) -> Result<(), anyhow::Error> {
+        let listener = TcpListener::bind(addr)?;
+        thread::scope(|scope| {
+            for _ in 0..SERVE_WORKERS {
+                scope.spawn(|| loop {
+                    // 单个连接出错不影响服务
+                    match listener.accept() {
+                        Ok((stream, _)) => self.serve_connection(stream),
+                        Err(e) => {
+                            eprintln!("sponsor: accept failed: {}", e);
+                            thread::sleep(ACCEPT_BACKOFF);
+                        }
+                    }
+                });
+            }
+        });
+        Ok(())
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn serve_connection(&self, mut stream: TcpStream) {
+        let _ = stream.set_write_timeout(Some(IO_TIMEOUT));
+        let response = match read_request(DeadlineReader {
+            stream: &mut stream,
+            deadline: Instant::now() + IO_TIMEOUT,
+        }) {
+            Ok(request) if request.path != SPONSOR_PATH => {
+                Response::error("404 Not Found", "not found")
+            }
+            Ok(request) if request.method != "POST" => {
+                Response::error("405 Method Not Allowed", "expected a POST request")
+            }
+            Ok(request) => match self.handle_request(&request.body) {
+                Ok(body) => Response {
+                    status: "200 OK",
+                    content_type: "application/x-bcs",
+                    body,
+                },
+                Err(e) => Response::error("400 Bad Request", &e.to_string()),
+            },
+            Err(e) => Response::error("400 Bad Request", &e.to_string()),
+        };
+        let _ = write_response(&mut stream, &response);
+    }
+}
+
+/// Ask the sponsoring service at `sponsor_url`, e.g. `http://127.0.0.1:8090/sponsor`, to sign as
+/// fee payer and add its signature
+#[cfg(not(target_arch = "wasm32"))]
+pub fn request_sponsor_signature(
+    sponsor_url: &str,
+    transaction: &mut MultiAgentTransaction,
+) -> Result<(), anyhow::Error> {
+    let fee_payer = transaction
+        .fee_payer()
+        .ok_or_else(|| anyhow::anyhow!("transaction has no fee payer"))?;
+    let response = match ureq::post(sponsor_url)
+        .set("Content-Type", "application/x-bcs")
+        .send_bytes(&bcs::to_bytes(transaction)?)
+    {
+        Ok(response) => response,
+        Err(ureq::Error::Status(_, response)) => {
+            return Err(anyhow::anyhow!(
+                "sponsor rejected the transaction: {}",
+                response.into_string()?
+            ))
+        }
+        Err(e) => return Err(e.into()),
+    };
+    let mut bytes = vec![];
+    response.into_reader().read_to_end(&mut bytes)?;
+    transaction.add_authenticator(fee_payer, bcs::from_bytes(&bytes)?)
+}
+
+// 整个请求须在 deadline 前读完, 防止逐字节发送的慢连接长期占用 worker
+#[cfg(not(target_arch = "wasm32"))]
+struct DeadlineReader<'a> {
+    stream: &'a mut TcpStream,
+    deadline: Instant,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Read for DeadlineReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let remaining = self
+            .deadline
+            .checked_duration_since(Instant::now())
+            .filter(|remaining| !remaining.is_zero())
+            .ok_or_else(|| io::Error::new(io::ErrorKind::TimedOut, "request timed out"))?;
+        self.stream.set_read_timeout(Some(remaining))?;
+        self.stream.read(buf)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+struct Request {
+    method: String,
+    path: String,
+    body: Vec<u8>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+struct Response {
+    status: &'static str,
+    content_type: &'static str,
+    body: Vec<u8>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Response {
+    fn error(status: &'static str, message: &str) -> Self {
+        Self {
+            status,
+            content_type: "text/plain",
+            body: message.as_bytes().to_vec(),
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn read_request<R: Read>(stream: R) -> Result<Request, anyhow::Error> {
+    let mut reader = BufReader::new(stream);
+    // 请求行与请求头共用 MAX_HEADER_SIZE
+    let mut head = (&mut reader).take(MAX_HEADER_SIZE);
+    let mut line = String::new();
+    read_head_line(&mut head, &mut line)?;
+    let mut parts = line.split_whitespace();
+    let (method, path) = match (parts.next(), parts.next()) {
+        (Some(method), Some(path)) => (method.to_string(), path.to_string()),
+        _ => return Err(anyhow::anyhow!("invalid request line")),
+    };
+    let mut content_length = 0;
+    loop {
+        read_head_line(&mut head, &mut line)?;
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse::<usize>()?;
+            }
+        }
+    }
+    if content_length > MAX_REQUEST_SIZE {
+        return Err(anyhow::anyhow!("request body too large"));
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body)?;
+    Ok(Request { method, path, body })
+}
+
+// 读取一行, 超出请求头上限时没有换行符
+#[cfg(not(target_arch = "wasm32"))]
+fn read_head_line(head: &mut impl BufRead, line: &mut String) -> Result<(), anyhow::Error> {
+    line.clear();
+    head.read_line(line)?;
+    if !line.ends_with('\n') {
+        return Err(anyhow::anyhow!("request headers too large or incomplete"));
+    }
+    Ok(())
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn write_response(stream: &mut TcpStream, response: &Response) -> Result<(), anyhow::Error> {
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        response.status,
+        response.content_type,
+        response.body.len()
+    )?;
+    stream.write_all(&response.body)?;
+    Ok(())
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use aptos_crypto::ed25519::{Ed25519PrivateKey, Ed25519PublicKey};
+    use aptos_types::transaction::{authenticator::AuthenticationKey, EntryFunction, Script};
+    use move_core_types::{identifier::Identifier, language_storage::ModuleId};
+    use std::io::Cursor;
+
+    fn transfer_payload() -> TransactionPayload {
+        TransactionPayload::EntryFunction(EntryFunction::new(
+            ModuleId::new(
+                AccountAddress::ONE,
+                Identifier::new("aptos_account").unwrap(),
+            ),
+            Identifier::new("transfer").unwrap(),
+            vec![],
+            vec![],
+        ))
+    }
+
+    fn raw_transaction(
+        payload: TransactionPayload,
+        max_gas_amount: u64,
+        gas_unit_price: u64,
+    ) -> RawTransaction {
+        RawTransaction::new(
+            AccountAddress::ONE,
+            0,
+            payload,
+            max_gas_amount,
+            gas_unit_price,
+            u64::MAX,
+            ChainId::test(),
+        )
+    }
+
+    // 地址由私钥派生的本地账户
+    fn local_account(seed: u8) -> LocalAccount {
+        let private_key = Ed25519PrivateKey::try_from(&[seed; 32][..]).unwrap();
+        let address =
+            AuthenticationKey::ed25519(&Ed25519PublicKey::from(&private_key)).derived_address();
+        LocalAccount::new(address, private_key, 0)
+    }
+
+    fn fee_payer_transaction(
+        sender: AccountAddress,
+        secondary_signers: Vec<AccountAddress>,
+        sponsor: &Sponsor,
+    ) -> MultiAgentTransaction {
+        let raw_transaction = RawTransaction::new(
+            sender,
+            0,
+            transfer_payload(),
+            1_000,
+            100,
+            u64::MAX,
+            ChainId::test(),
+        );
+        MultiAgentTransaction::with_fee_payer(raw_transaction, secondary_signers, sponsor.address())
+    }
+
+    fn sponsor() -> Sponsor {
+        Sponsor::new(
+            local_account(7),
+            SponsorPolicy::new(ChainId::test(), 1_000, 100),
+        )
+    }
+
+    #[test]
+    fn policy_gas_limits() {
+        let policy = SponsorPolicy::new(ChainId::test(), 1_000, 100);
+        assert!(policy
+            .check(&raw_transaction(transfer_payload(), 1_000, 100))
+            .is_ok());
+        assert!(policy
+            .check(&raw_transaction(transfer_payload(), 1_001, 100))
+            .is_err());
+        assert!(policy
+            .check(&raw_transaction(transfer_payload(), 1_000, 101))
+            .is_err());
+    }
+
+    #[test]
+    fn policy_allow_lists() {
+        let mut policy = SponsorPolicy::new(ChainId::test(), 1_000, 100);
+        policy.allowed_functions = vec!["0x1::aptos_account::transfer".to_string()];
+        assert!(policy
+            .check(&raw_transaction(transfer_payload(), 1_000, 100))
+            .is_ok());
+        policy.allowed_functions = vec!["0x1::coin::transfer".to_string()];
+        assert!(policy
+            .check(&raw_transaction(transfer_payload(), 1_000, 100))
+            .is_err());
+        policy.allowed_functions = vec![];
+        policy.allowed_senders = vec![AccountAddress::from_hex_literal("0x2").unwrap()];
+        assert!(policy
+            .check(&raw_transaction(transfer_payload(), 1_000, 100))
+            .is_err());
+    }
+
+    #[test]
+    fn policy_chain_id() {
+        let policy = SponsorPolicy::new(ChainId::new(1), 1_000, 100);
+        assert!(policy
+            .check(&raw_transaction(transfer_payload(), 1_000, 100))
+            .is_err());
+    }
+
+    #[test]
+    fn sponsored_transaction_verifies() {
+        let sponsor = sponsor();
+        let sender = local_account(1);
+        let secondary = local_account(2);
+        let mut transaction =
+            fee_payer_transaction(sender.address(), vec![secondary.address()], &sponsor);
+        transaction.sign_and_add(&sender).unwrap();
+        transaction.sign_and_add(&secondary).unwrap();
+        // 经 BCS 发送给 sponsor 服务
+        let authenticator = bcs::from_bytes(
+            &sponsor
+                .handle_request(&bcs::to_bytes(&transaction).unwrap())
+                .unwrap(),
+        )
+        .unwrap();
+        transaction
+            .add_authenticator(sponsor.address(), authenticator)
+            .unwrap();
+        let signed = transaction.into_signed_transaction().unwrap();
+        assert!(signed.verify_signature().is_ok());
+    }
+
+    #[test]
+    fn sponsor_only_signs_as_fee_payer() {
+        let sponsor = sponsor();
+        let sender = local_account(1);
+        // sender 签名可被替换为 fee payer 签名, 从 sponsor 账户转出资金
+        let as_sender = fee_payer_transaction(sponsor.address(), vec![], &sponsor);
+        assert!(sponsor.sign(&as_sender).is_err());
+        let as_secondary =
+            fee_payer_transaction(sender.address(), vec![sponsor.address()], &sponsor);
+        assert!(sponsor.sign(&as_secondary).is_err());
+        let other_fee_payer = MultiAgentTransaction::with_fee_payer(
+            as_secondary.raw_transaction().clone(),
+            vec![],
+            sender.address(),
+        );
+        assert!(sponsor.sign(&other_fee_payer).is_err());
+        assert!(sponsor
+            .sign(&fee_payer_transaction(sender.address(), vec![], &sponsor))
+            .is_ok());
+    }
+
+    #[test]
+    fn policy_scripts() {
+        let script = TransactionPayload::Script(Script::new(vec![], vec![], vec![]));
+        let mut policy = SponsorPolicy::new(ChainId::test(), 1_000, 100);
+        assert!(policy
+            .check(&raw_transaction(script.clone(), 1_000, 100))
+            .is_err());
+        policy.allow_scripts = true;
+        assert!(policy.check(&raw_transaction(script, 1_000, 100)).is_ok());
+    }
+
+    #[test]
+    fn request_parsing() {
+        let request = read_request(Cursor::new(
+            b"POST /sponsor HTTP/1.1\r\nContent-Length: 3\r\n\r\nabc",
+        ))
+        .unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, SPONSOR_PATH);
+        assert_eq!(request.body, b"abc");
+    }
+
+    #[test]
+    fn request_limits() {
+        let mut long_header = b"POST /sponsor HTTP/1.1\r\nX-Padding: ".to_vec();
+        long_header.extend(vec![b'a'; MAX_HEADER_SIZE as usize]);
+        long_header.extend(b"\r\n\r\n");
+        assert!(read_request(Cursor::new(long_header)).is_err());
+
+        let large_body = format!(
+            "POST /sponsor HTTP/1.1\r\nContent-Length: {}\r\n\r\n",
+            MAX_REQUEST_SIZE + 1
+        );
+        assert!(read_request(Cursor::new(large_body.into_bytes())).is_err());
+    }
+
+    // 通过本地 TCP 连接发送请求, 返回完整的响应
+    fn exchange(request: &'static [u8]) -> String {
+        let sponsor = sponsor();
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let client = thread::spawn(move || {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            stream.write_all(request).unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        });
+        let (stream, _) = listener.accept().unwrap();
+        sponsor.serve_connection(stream);
+        client.join().unwrap()
+    }
+
+    #[test]
+    fn serve_routes() {
+        assert!(
+            exchange(b"POST /other HTTP/1.1\r\nContent-Length: 0\r\n\r\n")
+                .starts_with("HTTP/1.1 404 Not Found\r\n")
+        );
+        assert!(exchange(b"GET /sponsor HTTP/1.1\r\n\r\n")
+            .starts_with("HTTP/1.1 405 Method Not Allowed\r\n"));
+        assert!(
+            exchange(b"POST /sponsor HTTP/1.1\r\nContent-Length: 3\r\n\r\nabc")
+                .starts_with("HTTP/1.1 400 Bad Request\r\n")
+        );
+    }
+}
@@ -1,13 +1,22 @@
 use crate::{
-    client::Client,
-    types::{LocalAccount, U64},
+    client::{Client, Event, Table},
+    entry_function::{EntryFunctionCall, MoveArgument},
+    property_map::PropertyMap,
+    types::{struct_type_tag, HexBytes, LocalAccount, MoveType, U64},
 };
-use aptos_types::{
-    account_address::AccountAddress,
-    transaction::{EntryFunction, TransactionPayload},
+use aptos_types::account_address::AccountAddress;
+use move_core_types::{
+    identifier::Identifier,
+    language_storage::{ModuleId, TypeTag},
 };
-use move_core_types::{identifier::Identifier, language_storage::ModuleId};
-use serde::Deserialize;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+#[cfg(not(target_arch = "wasm32"))]
+use std::thread;
+
+const EVENT_PAGE_SIZE: u64 = 100;
+#[cfg(not(target_arch = "wasm32"))]
+const MAX_CONCURRENT_REQUESTS: usize = 8;
 
 #[derive(Debug)]
 pub struct TokenClient {
@@ -29,25 +38,36 @@ impl TokenClient {
         description: &str,
         uri: &str,
     ) -> Result<String, anyhow::Error> {
-        let payload = TransactionPayload::EntryFunction(EntryFunction::new(
-            ModuleId::new(
-                AccountAddress::from_hex_literal("0x3").unwrap(),
-                Identifier::new("token").unwrap(),
-            ),
-            Identifier::new("create_collection_script").unwrap(),
-            vec![],
+        self.create_collection_with_options(
+            account,
+            name,
+            description,
+            uri,
+            &CollectionOptions::default(),
+        )
+    }
+
+    // 创建 collection, 可设置 maximum 与可变性
+    pub fn create_collection_with_options(
+        &self,
+        account: LocalAccount,
+        name: &str,
+        description: &str,
+        uri: &str,
+        options: &CollectionOptions,
+    ) -> Result<String, anyhow::Error> {
+        self.submit_token_script(
+            account,
+            "token",
+            "create_collection_script",
             vec![
-                bcs::to_bytes(name).unwrap(),
-                bcs::to_bytes(description).unwrap(),
-                bcs::to_bytes(uri).unwrap(),
-                bcs::to_bytes(&u64::MAX).unwrap(),
-                bcs::to_bytes(&vec![false, false, false]).unwrap(),
+                name.into(),
+                description.into(),
+                uri.into(),
+                options.maximum.into(),
+                MoveArgument::vector(options.mutate_setting()),
             ],
-        ));
-        let signed_transaction = self
-            .client
-            .create_single_signer_bcs_transaction(account, payload);
-        Ok(self.client.submit_bcs_transaction(signed_transaction)?)
+        )
     }
 
     // 创建 token
@@ -60,37 +80,52 @@ impl TokenClient {
         supply: u64,
         uri: &str,
         royalty_points_per_million: u64,
-        property_keys: Vec<&str>,
-        property_values: Vec<&str>,
-        property_types: Vec<&str>,
+        properties: &PropertyMap,
     ) -> Result<String, anyhow::Error> {
-        let payload = TransactionPayload::EntryFunction(EntryFunction::new(
-            ModuleId::new(
-                AccountAddress::from_hex_literal("0x3").unwrap(),
-                Identifier::new("token").unwrap(),
-            ),
-            Identifier::new("create_token_script").unwrap(),
-            vec![],
+        self.create_token_with_options(
+            account,
+            collection_name,
+            name,
+            description,
+            uri,
+            properties,
+            &TokenOptions::new(supply, royalty_points_per_million),
+        )
+    }
+
+    // 创建 token, 可设置数量, 版税与可变性
+    pub fn create_token_with_options(
+        &self,
+        account: LocalAccount,
+        collection_name: &str,
+        name: &str,
+        description: &str,
+        uri: &str,
+        properties: &PropertyMap,
+        options: &TokenOptions,
+    ) -> Result<String, anyhow::Error> {
+        let (property_keys, property_values, property_types) = properties.encode()?;
+        let royalty_payee = options.royalty_payee.unwrap_or_else(|| account.address());
+        self.submit_token_script(
+            account,
+            "token",
+            "create_token_script",
             vec![
-                bcs::to_bytes(collection_name).unwrap(),
-                bcs::to_bytes(name).unwrap(),
-                bcs::to_bytes(description).unwrap(),
-                bcs::to_bytes(&supply).unwrap(),
-                bcs::to_bytes(&supply).unwrap(),
-                bcs::to_bytes(uri).unwrap(),
-                bcs::to_bytes(&account.address()).unwrap(),
-                bcs::to_bytes(&1000_000_u64).unwrap(),
-                bcs::to_bytes(&royalty_points_per_million).unwrap(),
-                bcs::to_bytes(&vec![false, false, false, false, false]).unwrap(),
-                bcs::to_bytes(&property_keys).unwrap(),
-                bcs::to_bytes(&property_values).unwrap(),
-                bcs::to_bytes(&property_types).unwrap(),
+                collection_name.into(),
+                name.into(),
+                description.into(),
+                options.initial_balance.into(),
+                options.maximum.into(),
+                uri.into(),
+                royalty_payee.into(),
+                options.royalty_denominator.into(),
+                options.royalty_numerator.into(),
+                MoveArgument::vector(options.mutate_setting()),
+                MoveArgument::vector(property_keys),
+                MoveArgument::vector(property_values),
+                MoveArgument::vector(property_types),
             ],
-        ));
-        let signed_transaction = self
-            .client
-            .create_single_signer_bcs_transaction(account, payload);
-        Ok(self.client.submit_bcs_transaction(signed_transaction)?)
+        )
     }
 
     // 发送 token
@@ -104,26 +139,19 @@ impl TokenClient {
         property_version: u64,
         amount: u64,
     ) -> Result<String, anyhow::Error> {
-        let payload = TransactionPayload::EntryFunction(EntryFunction::new(
-            ModuleId::new(
-                AccountAddress::from_hex_literal("0x3").unwrap(),
-                Identifier::new("token_transfers").unwrap(),
-            ),
-            Identifier::new("offer_script").unwrap(),
-            vec![],
+        self.submit_token_script(
+            account,
+            "token_transfers",
+            "offer_script",
             vec![
-                bcs::to_bytes(&receiver.to_hex_literal()).unwrap(),
-                bcs::to_bytes(&creator.to_hex_literal()).unwrap(),
-                bcs::to_bytes(collection_name).unwrap(),
-                bcs::to_bytes(token_name).unwrap(),
-                bcs::to_bytes(&property_version).unwrap(),
-                bcs::to_bytes(&amount).unwrap(),
+                receiver.into(),
+                creator.into(),
+                collection_name.into(),
+                token_name.into(),
+                property_version.into(),
+                amount.into(),
             ],
-        ));
-        let signed_transaction = self
-            .client
-            .create_single_signer_bcs_transaction(account, payload);
-        Ok(self.client.submit_bcs_transaction(signed_transaction)?)
+        )
     }
 
     // 索要 token
@@ -136,25 +164,354 @@ impl TokenClient {
         token_name: &str,
         property_version: u64,
     ) -> Result<String, anyhow::Error> {
-        let payload = TransactionPayload::EntryFunction(EntryFunction::new(
-            ModuleId::new(
-                AccountAddress::from_hex_literal("0x3").unwrap(),
-                Identifier::new("token_transfers").unwrap(),
-            ),
-            Identifier::new("claim_script").unwrap(),
-            vec![],
+        self.submit_token_script(
+            account,
+            "token_transfers",
+            "claim_script",
+            vec![
+                sender.into(),
+                creator.into(),
+                collection_name.into(),
+                token_name.into(),
+                property_version.into(),
+            ],
+        )
+    }
+
+    // 取消 offer
+    pub fn cancel_offer(
+        &self,
+        account: LocalAccount,
+        receiver: AccountAddress,
+        creator: AccountAddress,
+        collection_name: &str,
+        token_name: &str,
+        property_version: u64,
+    ) -> Result<String, anyhow::Error> {
+        self.submit_token_script(
+            account,
+            "token_transfers",
+            "cancel_offer_script",
+            vec![
+                receiver.into(),
+                creator.into(),
+                collection_name.into(),
+                token_name.into(),
+                property_version.into(),
+            ],
+        )
+    }
+
+    // 直接转移 token, sender 与 receiver 共同签名 (multi-agent)
+    pub fn direct_transfer(
+        &self,
+        sender: LocalAccount,
+        receiver: LocalAccount,
+        creator: AccountAddress,
+        collection_name: &str,
+        token_name: &str,
+        property_version: u64,
+        amount: u64,
+    ) -> Result<String, anyhow::Error> {
+        let payload = self
+            .client
+            .checked_entry_function_payload(&EntryFunctionCall::new(
+                token_module("token")?,
+                "direct_transfer_script",
+                vec![],
+                vec![
+                    creator.into(),
+                    collection_name.into(),
+                    token_name.into(),
+                    property_version.into(),
+                    amount.into(),
+                ],
+            ))?;
+        let mut transaction = self.client.build_multi_agent_transaction(
+            sender.address(),
+            vec![receiver.address()],
+            payload,
+        )?;
+        transaction.sign_and_add(&sender)?;
+        transaction.sign_and_add(&receiver)?;
+        self.client.submit_multi_agent_transaction(transaction)
+    }
+
+    // 是否接受直接转入的 token
+    pub fn opt_in_direct_transfer(
+        &self,
+        account: LocalAccount,
+        opt_in: bool,
+    ) -> Result<String, anyhow::Error> {
+        self.submit_token_script(
+            account,
+            "token",
+            "opt_in_direct_transfer",
+            vec![opt_in.into()],
+        )
+    }
+
+    // 转移 token 给已 opt in 的账户
+    pub fn transfer_with_opt_in(
+        &self,
+        account: LocalAccount,
+        creator: AccountAddress,
+        collection_name: &str,
+        token_name: &str,
+        property_version: u64,
+        receiver: AccountAddress,
+        amount: u64,
+    ) -> Result<String, anyhow::Error> {
+        self.submit_token_script(
+            account,
+            "token",
+            "transfer_with_opt_in",
+            vec![
+                creator.into(),
+                collection_name.into(),
+                token_name.into(),
+                property_version.into(),
+                receiver.into(),
+                amount.into(),
+            ],
+        )
+    }
+
+    // 初始化 TokenStore
+    pub fn initialize_token_store(&self, account: LocalAccount) -> Result<String, anyhow::Error> {
+        self.submit_token_script(account, "token", "initialize_token_script", vec![])
+    }
+
+    // 持有者销毁 token
+    pub fn burn(
+        &self,
+        account: LocalAccount,
+        creator: AccountAddress,
+        collection_name: &str,
+        token_name: &str,
+        property_version: u64,
+        amount: u64,
+    ) -> Result<String, anyhow::Error> {
+        self.submit_token_script(
+            account,
+            "token",
+            "burn",
+            vec![
+                creator.into(),
+                collection_name.into(),
+                token_name.into(),
+                property_version.into(),
+                amount.into(),
+            ],
+        )
+    }
+
+    // 创建者销毁 owner 持有的 token
+    pub fn burn_by_creator(
+        &self,
+        account: LocalAccount,
+        owner: AccountAddress,
+        collection_name: &str,
+        token_name: &str,
+        property_version: u64,
+        amount: u64,
+    ) -> Result<String, anyhow::Error> {
+        self.submit_token_script(
+            account,
+            "token",
+            "burn_by_creator",
+            vec![
+                owner.into(),
+                collection_name.into(),
+                token_name.into(),
+                property_version.into(),
+                amount.into(),
+            ],
+        )
+    }
+
+    // 增发 token
+    pub fn mint(
+        &self,
+        account: LocalAccount,
+        token_data_address: AccountAddress,
+        collection_name: &str,
+        token_name: &str,
+        amount: u64,
+    ) -> Result<String, anyhow::Error> {
+        self.submit_token_script(
+            account,
+            "token",
+            "mint_script",
+            vec![
+                token_data_address.into(),
+                collection_name.into(),
+                token_name.into(),
+                amount.into(),
+            ],
+        )
+    }
+
+    // 修改 token 属性
+    pub fn mutate_token_properties(
+        &self,
+        account: LocalAccount,
+        token_owner: AccountAddress,
+        creator: AccountAddress,
+        collection_name: &str,
+        token_name: &str,
+        property_version: u64,
+        amount: u64,
+        properties: &PropertyMap,
+    ) -> Result<String, anyhow::Error> {
+        let (keys, values, types) = properties.encode()?;
+        self.submit_token_script(
+            account,
+            "token",
+            "mutate_token_properties",
             vec![
-                bcs::to_bytes(&sender.to_hex_literal()).unwrap(),
-                bcs::to_bytes(&creator.to_hex_literal()).unwrap(),
-                bcs::to_bytes(collection_name).unwrap(),
-                bcs::to_bytes(token_name).unwrap(),
-                bcs::to_bytes(&property_version).unwrap(),
+                token_owner.into(),
+                creator.into(),
+                collection_name.into(),
+                token_name.into(),
+                property_version.into(),
+                amount.into(),
+                MoveArgument::vector(keys),
+                MoveArgument::vector(values),
+                MoveArgument::vector(types),
             ],
-        ));
-        let signed_transaction = self
+        )
+    }
+
+    // sender 发出且尚未被领取或取消的 offer
+    pub fn pending_offers(
+        &self,
+        sender: AccountAddress,
+    ) -> Result<Vec<PendingOffer>, anyhow::Error> {
+        let pending_claims = match self
             .client
-            .create_single_signer_bcs_transaction(account, payload);
-        Ok(self.client.submit_bcs_transaction(signed_transaction)?)
+            .account_resource_opt::<PendingClaims>(sender, "0x3::token_transfers::PendingClaims")?
+        {
+            Some(resource) => resource.data.pending_claims,
+            None => return Ok(vec![]),
+        };
+        let events = self.all_events::<TokenOfferEvent>(
+            sender,
+            "0x3::token_transfers::PendingClaims",
+            "offer_events",
+        )?;
+        // 同一 offer 可能有多个事件, 只查询一次
+        let mut seen = HashSet::new();
+        let mut offers = vec![];
+        for event in events {
+            let offer_id = TokenOfferId {
+                to_addr: event.data.to_address,
+                token_id: event.data.token_id,
+            };
+            if !seen.insert(offer_id.clone()) {
+                continue;
+            }
+            if let Some(token) = pending_claims.try_get(&self.client, &offer_id)? {
+                offers.push(PendingOffer {
+                    sender,
+                    receiver: offer_id.to_addr,
+                    token_id: offer_id.token_id,
+                    amount: token.amount.0,
+                });
+            }
+        }
+        Ok(offers)
+    }
+
+    // receiver 可从 senders 领取的 offer
+    // offer 只记录在发送方的 PendingClaims 中, 接收方没有对应的资源或事件, 无法只凭 receiver 查询
+    pub fn pending_claims_from(
+        &self,
+        receiver: AccountAddress,
+        senders: &[AccountAddress],
+    ) -> Result<Vec<PendingOffer>, anyhow::Error> {
+        let mut offers = vec![];
+        for sender in senders {
+            offers.extend(
+                self.pending_offers(*sender)?
+                    .into_iter()
+                    .filter(|offer| offer.receiver == receiver),
+            );
+        }
+        Ok(offers)
+    }
+
+    // creator 的 0x3::token_event_store::TokenEventStoreV1 中的修改事件, 按发生顺序分页
+    // field_name 如 description_mutate_events, T 为对应的事件数据
+    pub fn mutation_events<T: DeserializeOwned>(
+        &self,
+        creator: AccountAddress,
+        field_name: &str,
+        start: u64,
+        limit: u64,
+    ) -> Result<Vec<Event<T>>, anyhow::Error> {
+        self.client.events_by_event_handle::<T>(
+            creator,
+            "0x3::token_event_store::TokenEventStoreV1",
+            field_name,
+            Some(limit),
+            Some(U64(start)),
+        )
+    }
+
+    // token description 修改记录
+    pub fn description_mutations(
+        &self,
+        creator: AccountAddress,
+        start: u64,
+        limit: u64,
+    ) -> Result<Vec<Event<DescriptionMutateEvent>>, anyhow::Error> {
+        self.mutation_events(creator, "description_mutate_events", start, limit)
+    }
+
+    // token uri 修改记录
+    pub fn uri_mutations(
+        &self,
+        creator: AccountAddress,
+        start: u64,
+        limit: u64,
+    ) -> Result<Vec<Event<UriMutationEvent>>, anyhow::Error> {
+        self.mutation_events(creator, "uri_mutate_events", start, limit)
+    }
+
+    // token 版税修改记录
+    pub fn royalty_mutations(
+        &self,
+        creator: AccountAddress,
+        start: u64,
+        limit: u64,
+    ) -> Result<Vec<Event<RoyaltyMutateEvent>>, anyhow::Error> {
+        self.mutation_events(creator, "royalty_mutate_events", start, limit)
+    }
+
+    // collection description 修改记录
+    pub fn collection_description_mutations(
+        &self,
+        creator: AccountAddress,
+        start: u64,
+        limit: u64,
+    ) -> Result<Vec<Event<CollectionDescriptionMutateEvent>>, anyhow::Error> {
+        self.mutation_events(
+            creator,
+            "collection_description_mutate_events",
+            start,
+            limit,
+        )
+    }
+
+    // collection uri 修改记录
+    pub fn collection_uri_mutations(
+        &self,
+        creator: AccountAddress,
+        start: u64,
+        limit: u64,
+    ) -> Result<Vec<Event<CollectionUriMutateEvent>>, anyhow::Error> {
+        self.mutation_events(creator, "collection_uri_mutate_events", start, limit)
     }
 
     // collection 数据
@@ -163,13 +520,56 @@ impl TokenClient {
         account_address: AccountAddress,
         collection_name: &str,
     ) -> Result<CollectionData, anyhow::Error> {
-        Ok(self.client.table_item::<CollectionData>(
-            &self.account_resource_collection_data_handle(account_address)?,
-            "0x1::string::String",
-            "0x3::token::CollectionData",
-            serde_json::json!(collection_name),
-            None,
-        )?)
+        Ok(self
+            .collections(account_address)?
+            .collection_data
+            .get(&self.client, &collection_name.to_string())?)
+    }
+
+    // creator 创建的 collection, 按创建顺序分页
+    pub fn collections_by_creator(
+        &self,
+        creator: AccountAddress,
+        start: u64,
+        limit: u64,
+    ) -> Result<Vec<CollectionData>, anyhow::Error> {
+        let events = self
+            .client
+            .events_by_event_handle::<CreateCollectionEvent>(
+                creator,
+                "0x3::token::Collections",
+                "create_collection_events",
+                Some(limit),
+                Some(U64(start)),
+            )?;
+        let collection_data = self.collections(creator)?.collection_data;
+        fetch_bounded(&events, |event| {
+            collection_data.get(&self.client, &event.data.collection_name)
+        })
+    }
+
+    // collection 中的 token, 按创建顺序分页
+    pub fn tokens_in_collection(
+        &self,
+        creator: AccountAddress,
+        collection_name: &str,
+        start: u64,
+        limit: u64,
+    ) -> Result<Vec<TokenData>, anyhow::Error> {
+        let token_data_ids: Vec<TokenDataId> = self
+            .all_events::<CreateTokenDataEvent>(
+                creator,
+                "0x3::token::Collections",
+                "create_token_data_events",
+            )?
+            .into_iter()
+            .map(|event| event.data.id)
+            .filter(|id| id.collection == collection_name)
+            .skip(start as usize)
+            .take(limit as usize)
+            .collect();
+        let token_data = self.collections(creator)?.token_data;
+        fetch_bounded(&token_data_ids, |id| token_data.get(&self.client, id))
     }
 
     pub fn token(
@@ -180,21 +580,18 @@ impl TokenClient {
         token_name: &str,
         property_version: u64,
     ) -> Result<Token, anyhow::Error> {
-        let token_id = serde_json::json!({
-            "token_data_id": {
-                "creator": creater.to_hex_literal(),
-                "collection": collection_name,
-                "name": token_name,
+        let token_id = TokenId {
+            token_data_id: TokenDataId {
+                creator: creater,
+                collection: collection_name.to_string(),
+                name: token_name.to_string(),
             },
-            "property_version": U64(property_version),
-        });
-        Ok(self.client.table_item::<Token>(
-            &self.account_resource_tokens_handle(owner)?,
-            "0x3::token::TokenId",
-            "0x3::token::Token",
-            token_id,
-            None,
-        )?)
+            property_version: U64(property_version),
+        };
+        Ok(self
+            .token_store(owner)?
+            .tokens
+            .get_bcs(&self.client, &token_id)?)
     }
 
     pub fn token_data(
@@ -204,46 +601,74 @@ impl TokenClient {
         token_name: &str,
         _property_version: u64,
     ) -> Result<TokenData, anyhow::Error> {
-        let token_data_id = serde_json::json!({
-            "creator": creator.to_hex_literal(),
-            "collection": collection_name,
-            "name": token_name,
-        });
-        Ok(self.client.table_item::<TokenData>(
-            &self.account_resource_token_data_handle(creator)?,
-            "0x3::token::TokenDataId",
-            "0x3::token::TokenData",
-            token_data_id,
-            None,
-        )?)
+        let token_data_id = TokenDataId {
+            creator,
+            collection: collection_name.to_string(),
+            name: token_name.to_string(),
+        };
+        Ok(self
+            .collections(creator)?
+            .token_data
+            .get(&self.client, &token_data_id)?)
     }
 
+    // 账户当前持有的 token, 按首次转入顺序分页
+    // 每次调用都会读取全部 deposit 与 withdraw 事件再分页, 开销随账户历史线性增长
+    // 已销毁 (不再在 TokenStore 中) 的 token 被跳过, 因此一页可能少于 limit 个
     pub fn list_account_token_data(
         &self,
         account_address: AccountAddress,
-        _start: u64,
-        _limit: u64,
-    ) -> Result<Vec<TokenData>, anyhow::Error> {
-        let events = self.client.events_by_event_handle::<EventData>(
+        start: u64,
+        limit: u64,
+    ) -> Result<Vec<OwnedToken>, anyhow::Error> {
+        let deposits = self.all_events::<EventData>(
             account_address,
             "0x3::token::TokenStore",
             "deposit_events",
-            None,
-            None,
         )?;
-        let mut tokens = vec![];
-        for e in events {
-            let token_data_id = e.data.id.token_data_id;
-            let token = self.token_data(
-                // account_address,
-                token_data_id.creator,
-                &token_data_id.collection,
-                &token_data_id.name,
-                0,
-            )?;
-            tokens.push(token);
+        let withdrawals = self.all_events::<EventData>(
+            account_address,
+            "0x3::token::TokenStore",
+            "withdraw_events",
+        )?;
+        let page = held_token_page(
+            deposits.into_iter().map(|event| event.data),
+            withdrawals.into_iter().map(|event| event.data),
+            start,
+            limit,
+        );
+        // 当前数量以 TokenStore 为准
+        let tokens = self.token_store(account_address)?.tokens;
+        // 每个创建者只读取一次 Collections
+        let mut creators: Vec<AccountAddress> = vec![];
+        for id in &page {
+            if !creators.contains(&id.token_data_id.creator) {
+                creators.push(id.token_data_id.creator);
+            }
         }
-        Ok(tokens)
+        let collections: HashMap<AccountAddress, Collections> = creators
+            .iter()
+            .copied()
+            .zip(fetch_bounded(&creators, |creator| {
+                self.collections(*creator)
+            })?)
+            .collect();
+        // burn 不产生 withdraw 事件, 事件中仍持有的 token 可能已不在 TokenStore 中
+        let owned = fetch_bounded(&page, |id| {
+            let token = match tokens.try_get(&self.client, id)? {
+                Some(token) => token,
+                None => return Ok(None),
+            };
+            let data = collections[&id.token_data_id.creator]
+                .token_data
+                .get(&self.client, &id.token_data_id)?;
+            Ok(Some(OwnedToken {
+                id: token.id,
+                amount: token.amount.0,
+                data,
+            }))
+        })?;
+        Ok(owned.into_iter().flatten().collect())
     }
 
     pub fn token_balance(
@@ -264,50 +689,236 @@ impl TokenClient {
         Ok(resp.amount.0)
     }
 
-    // 以下为私有方法
-    fn account_resource_collection_data_handle(
-        &self,
-        account_address: AccountAddress,
-    ) -> Result<String, anyhow::Error> {
+    // 0x3::token::Collections 资源
+    pub fn collections(&self, creator: AccountAddress) -> Result<Collections, anyhow::Error> {
         Ok(self
             .client
-            .account_resource::<ResourceCollectionData>(account_address, "0x3::token::Collections")?
-            .data
-            .collection_data
-            .handle)
+            .account_resource::<Collections>(creator, "0x3::token::Collections")?
+            .data)
     }
 
-    fn account_resource_token_data_handle(
-        &self,
-        account_address: AccountAddress,
-    ) -> Result<String, anyhow::Error> {
+    // 0x3::token::TokenStore 资源
+    pub fn token_store(&self, owner: AccountAddress) -> Result<TokenStore, anyhow::Error> {
         Ok(self
             .client
-            .account_resource::<ResourceTokenData>(account_address, "0x3::token::Collections")?
-            .data
-            .token_data
-            .handle)
+            .account_resource::<TokenStore>(owner, "0x3::token::TokenStore")?
+            .data)
     }
 
-    fn account_resource_tokens_handle(
+    // 以下为私有方法
+    // 分页读取事件直到最后一页
+    fn all_events<T: DeserializeOwned>(
         &self,
         account_address: AccountAddress,
+        event_handle: &str,
+        field_name: &str,
+    ) -> Result<Vec<Event<T>>, anyhow::Error> {
+        let mut events = vec![];
+        loop {
+            let page = self.client.events_by_event_handle::<T>(
+                account_address,
+                event_handle,
+                field_name,
+                Some(EVENT_PAGE_SIZE),
+                Some(U64(events.len() as u64)),
+            )?;
+            let done = (page.len() as u64) < EVENT_PAGE_SIZE;
+            events.extend(page);
+            if done {
+                return Ok(events);
+            }
+        }
+    }
+
+    fn submit_token_script(
+        &self,
+        account: LocalAccount,
+        module: &str,
+        function: &str,
+        args: Vec<MoveArgument>,
     ) -> Result<String, anyhow::Error> {
-        Ok(self
-            .client
-            .account_resource::<ResourceTokens>(account_address, "0x3::token::TokenStore")?
-            .data
-            .tokens
-            .handle)
+        self.client.submit_entry_function_call(
+            account,
+            &EntryFunctionCall::new(token_module(module)?, function, vec![], args),
+        )
+    }
+}
+
+// 并发执行 f, 同时进行的请求数不超过 MAX_CONCURRENT_REQUESTS
+fn fetch_bounded<T: Sync, R: Send>(
+    items: &[T],
+    f: impl Fn(&T) -> Result<R, anyhow::Error> + Sync,
+) -> Result<Vec<R>, anyhow::Error> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let mut results = Vec::with_capacity(items.len());
+        for chunk in items.chunks(MAX_CONCURRENT_REQUESTS) {
+            let chunk_results = thread::scope(|scope| {
+                let handles: Vec<_> = chunk.iter().map(|item| scope.spawn(|| f(item))).collect();
+                handles
+                    .into_iter()
+                    .map(|handle| handle.join().expect("fetch thread panicked"))
+                    .collect::<Vec<_>>()
+            });
+            for result in chunk_results {
+                results.push(result?);
+            }
+        }
+        Ok(results)
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        items.iter().map(f).collect()
+    }
+}
+
+// 按 TokenId 汇总转入与转出, 仍持有的 token 按首次转入顺序分页
+fn held_token_page(
+    deposits: impl IntoIterator<Item = EventData>,
+    withdrawals: impl IntoIterator<Item = EventData>,
+    start: u64,
+    limit: u64,
+) -> Vec<TokenId> {
+    let mut token_ids: Vec<TokenId> = vec![];
+    let mut amounts: HashMap<TokenId, i128> = HashMap::new();
+    for deposit in deposits {
+        if !amounts.contains_key(&deposit.id) {
+            token_ids.push(deposit.id.clone());
+        }
+        *amounts.entry(deposit.id).or_default() += deposit.amount.0 as i128;
+    }
+    for withdrawal in withdrawals {
+        *amounts.entry(withdrawal.id).or_default() -= withdrawal.amount.0 as i128;
+    }
+    token_ids
+        .into_iter()
+        .filter(|id| amounts.get(id).map_or(false, |amount| *amount > 0))
+        .skip(start as usize)
+        .take(limit as usize)
+        .collect()
+}
+
+fn token_module(name: &str) -> Result<ModuleId, anyhow::Error> {
+    Ok(ModuleId::new(
+        AccountAddress::from_hex_literal("0x3")?,
+        Identifier::new(name)?,
+    ))
+}
+
+/// Options of `create_collection_with_options`, defaults to an unlimited and immutable collection
+#[derive(Debug, Clone)]
+pub struct CollectionOptions {
+    pub maximum: u64,
+    pub mutable_description: bool,
+    pub mutable_uri: bool,
+    pub mutable_maximum: bool,
+}
+
+impl CollectionOptions {
+    // 顺序与 create_collection_script 的 mutate_setting 一致
+    fn mutate_setting(&self) -> Vec<bool> {
+        vec![
+            self.mutable_description,
+            self.mutable_uri,
+            self.mutable_maximum,
+        ]
+    }
+}
+
+impl Default for CollectionOptions {
+    fn default() -> Self {
+        Self {
+            maximum: u64::MAX,
+            mutable_description: false,
+            mutable_uri: false,
+            mutable_maximum: false,
+        }
+    }
+}
+
+/// Options of `create_token_with_options`, defaults to a single immutable token without royalty
+#[derive(Debug, Clone)]
+pub struct TokenOptions {
+    /// Amount minted to the creator
+    pub initial_balance: u64,
+    pub maximum: u64,
+    /// Royalty payee, the creator if None
+    pub royalty_payee: Option<AccountAddress>,
+    pub royalty_numerator: u64,
+    pub royalty_denominator: u64,
+    pub mutable_maximum: bool,
+    pub mutable_uri: bool,
+    pub mutable_royalty: bool,
+    pub mutable_description: bool,
+    pub mutable_properties: bool,
+}
+
+impl TokenOptions {
+    /// Mint `supply` tokens with a maximum of `supply`, royalty in points per million
+    pub fn new(supply: u64, royalty_points_per_million: u64) -> Self {
+        Self {
+            initial_balance: supply,
+            maximum: supply,
+            royalty_numerator: royalty_points_per_million,
+            ..Default::default()
+        }
+    }
+
+    // 顺序与 create_token_script 的 mutate_setting 一致
+    fn mutate_setting(&self) -> Vec<bool> {
+        vec![
+            self.mutable_maximum,
+            self.mutable_uri,
+            self.mutable_royalty,
+            self.mutable_description,
+            self.mutable_properties,
+        ]
+    }
+}
+
+impl Default for TokenOptions {
+    fn default() -> Self {
+        Self {
+            initial_balance: 1,
+            maximum: 1,
+            royalty_payee: None,
+            royalty_numerator: 0,
+            royalty_denominator: 1_000_000,
+            mutable_maximum: false,
+            mutable_uri: false,
+            mutable_royalty: false,
+            mutable_description: false,
+            mutable_properties: false,
+        }
     }
 }
 
+#[derive(Debug, Deserialize)]
+pub struct Collections {
+    pub collection_data: Table<String, CollectionData>,
+    pub token_data: Table<TokenDataId, TokenData>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TokenStore {
+    pub tokens: Table<TokenId, Token>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct CollectionData {
     pub name: String,
     pub description: String,
     pub uri: String,
     pub supply: U64,
+    pub maximum: U64,
+    pub mutability_config: CollectionMutabilityConfig,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CollectionMutabilityConfig {
+    pub description: bool,
+    pub uri: bool,
+    pub maximum: bool,
 }
 
 #[derive(Debug, Deserialize)]
@@ -316,45 +927,96 @@ pub struct TokenData {
     pub description: String,
     pub uri: String,
     pub supply: U64,
+    pub maximum: U64,
+    pub largest_property_version: U64,
+    pub royalty: Royalty,
+    pub mutability_config: TokenMutabilityConfig,
     pub default_properties: TokenDataPropertyMap,
 }
 
+#[derive(Debug, Clone, Deserialize)]
+pub struct Royalty {
+    pub royalty_points_numerator: U64,
+    pub royalty_points_denominator: U64,
+    pub payee_address: AccountAddress,
+}
+
+impl Royalty {
+    /// Royalty paid to `payee_address` for a sale at `sale_price`, rounded down
+    ///
+    /// A zero denominator pays nothing. A numerator above the denominator, which `0x3::token`
+    /// rejects, is clamped so the royalty is at most the sale price.
+    pub fn payout(&self, sale_price: u64) -> u64 {
+        let denominator = self.royalty_points_denominator.0;
+        if denominator == 0 {
+            return 0;
+        }
+        let numerator = self.royalty_points_numerator.0.min(denominator);
+        // 乘积可能超出 u64, 用 u128 计算, 结果不超过 sale_price
+        u64::try_from(sale_price as u128 * numerator as u128 / denominator as u128)
+            .unwrap_or(sale_price)
+    }
+}
+
 #[derive(Debug, Deserialize)]
+pub struct TokenMutabilityConfig {
+    pub maximum: bool,
+    pub uri: bool,
+    pub royalty: bool,
+    pub description: bool,
+    pub properties: bool,
+}
+
+impl TokenData {
+    pub fn properties(&self) -> Result<PropertyMap, anyhow::Error> {
+        PropertyMap::decode(&self.default_properties)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
 pub struct TokenDataPropertyMap {
     pub map: TokenDataPropertyData,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct TokenDataPropertyData {
     pub data: Vec<TokenDataPropertyItem>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct TokenDataPropertyItem {
     pub key: String,
     pub value: TokenDataPropertyItemValue,
 }
 
-#[derive(Debug, Deserialize)]
+// 字段顺序与 Move 结构一致, 以便 BCS 解码
+#[derive(Debug, Serialize, Deserialize)]
 pub struct TokenDataPropertyItemValue {
+    pub value: HexBytes,
     #[serde(rename = "type")]
     pub value_type: String,
-    pub value: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Token {
-    pub amount: U64,
     pub id: TokenId,
+    pub amount: U64,
+    pub token_properties: TokenDataPropertyMap,
 }
 
-#[derive(Debug, Deserialize)]
+impl Token {
+    pub fn properties(&self) -> Result<PropertyMap, anyhow::Error> {
+        PropertyMap::decode(&self.token_properties)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct TokenId {
-    pub property_version: U64,
     pub token_data_id: TokenDataId,
+    pub property_version: U64,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct TokenDataId {
     pub creator: AccountAddress,
     pub collection: String,
@@ -362,33 +1024,257 @@ pub struct TokenDataId {
 }
 
 #[derive(Debug, Deserialize)]
-pub struct EventData {
-    pub amount: U64,
-    pub id: EventId,
+pub struct PendingClaims {
+    pub pending_claims: Table<TokenOfferId, Token>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TokenOfferId {
+    pub to_addr: AccountAddress,
+    pub token_id: TokenId,
 }
 
+impl MoveType for TokenOfferId {
+    fn type_tag() -> TypeTag {
+        struct_type_tag(
+            AccountAddress::from_hex_literal("0x3").unwrap(),
+            "token_transfers",
+            "TokenOfferId",
+            vec![],
+        )
+    }
+}
+
+/// Data of the Collections create collection events
 #[derive(Debug, Deserialize)]
-pub struct EventId {
-    pub token_data_id: TokenDataId,
+pub struct CreateCollectionEvent {
+    pub creator: AccountAddress,
+    pub collection_name: String,
+    pub uri: String,
+    pub description: String,
+    pub maximum: U64,
 }
 
-// 以下私有方法使用
+/// Data of the Collections create token data events
 #[derive(Debug, Deserialize)]
-struct ResourceHandle {
-    pub handle: String,
+pub struct CreateTokenDataEvent {
+    pub id: TokenDataId,
+    pub description: String,
+    pub maximum: U64,
+    pub uri: String,
+    pub name: String,
+}
+
+/// Data of the TokenEventStoreV1 description mutate events
+#[derive(Debug, Deserialize)]
+pub struct DescriptionMutateEvent {
+    pub creator: AccountAddress,
+    pub collection: String,
+    pub token: String,
+    pub old_description: String,
+    pub new_description: String,
+}
+
+/// Data of the TokenEventStoreV1 uri mutate events
+#[derive(Debug, Deserialize)]
+pub struct UriMutationEvent {
+    pub creator: AccountAddress,
+    pub collection: String,
+    pub token: String,
+    pub old_uri: String,
+    pub new_uri: String,
+}
+
+/// Data of the TokenEventStoreV1 royalty mutate events
+#[derive(Debug, Deserialize)]
+pub struct RoyaltyMutateEvent {
+    pub creator: AccountAddress,
+    pub collection: String,
+    pub token: String,
+    pub old_royalty_numerator: U64,
+    pub old_royalty_denominator: U64,
+    pub old_royalty_payee_addr: AccountAddress,
+    pub new_royalty_numerator: U64,
+    pub new_royalty_denominator: U64,
+    pub new_royalty_payee_addr: AccountAddress,
 }
 
+/// Data of the TokenEventStoreV1 collection description mutate events
 #[derive(Debug, Deserialize)]
-struct ResourceCollectionData {
-    pub collection_data: ResourceHandle,
+pub struct CollectionDescriptionMutateEvent {
+    pub creator_addr: AccountAddress,
+    pub collection_name: String,
+    pub old_description: String,
+    pub new_description: String,
 }
 
+/// Data of the TokenEventStoreV1 collection uri mutate events
 #[derive(Debug, Deserialize)]
-struct ResourceTokenData {
-    pub token_data: ResourceHandle,
+pub struct CollectionUriMutateEvent {
+    pub creator_addr: AccountAddress,
+    pub collection_name: String,
+    pub old_uri: String,
+    pub new_uri: String,
 }
 
+/// Data of the PendingClaims offer events
 #[derive(Debug, Deserialize)]
-struct ResourceTokens {
-    pub tokens: ResourceHandle,
+pub struct TokenOfferEvent {
+    pub to_address: AccountAddress,
+    pub token_id: TokenId,
+    pub amount: U64,
+}
+
+/// An offered token waiting to be claimed by the receiver or cancelled by the sender
+#[derive(Debug, Clone)]
+pub struct PendingOffer {
+    pub sender: AccountAddress,
+    pub receiver: AccountAddress,
+    pub token_id: TokenId,
+    pub amount: u64,
+}
+
+/// A token held by an account, see `list_account_token_data`
+#[derive(Debug)]
+pub struct OwnedToken {
+    pub id: TokenId,
+    pub amount: u64,
+    pub data: TokenData,
+}
+
+/// Data of the TokenStore deposit and withdraw events
+#[derive(Debug, Deserialize)]
+pub struct EventData {
+    pub id: TokenId,
+    pub amount: U64,
+}
+
+macro_rules! impl_token_move_type {
+    ($($ty:ty => $name:expr),* $(,)?) => {
+        $(
+            impl MoveType for $ty {
+                fn type_tag() -> TypeTag {
+                    struct_type_tag(
+                        AccountAddress::from_hex_literal("0x3").unwrap(),
+                        "token",
+                        $name,
+                        vec![],
+                    )
+                }
+            }
+        )*
+    };
+}
+
+impl_token_move_type!(
+    CollectionData => "CollectionData",
+    TokenData => "TokenData",
+    Token => "Token",
+    TokenId => "TokenId",
+    TokenDataId => "TokenDataId",
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn royalty(numerator: u64, denominator: u64) -> Royalty {
+        Royalty {
+            royalty_points_numerator: U64(numerator),
+            royalty_points_denominator: U64(denominator),
+            payee_address: AccountAddress::ONE,
+        }
+    }
+
+    fn token_id(name: &str) -> TokenId {
+        TokenId {
+            token_data_id: TokenDataId {
+                creator: AccountAddress::ONE,
+                collection: "collection".to_string(),
+                name: name.to_string(),
+            },
+            property_version: U64(0),
+        }
+    }
+
+    fn event(name: &str, amount: u64) -> EventData {
+        EventData {
+            id: token_id(name),
+            amount: U64(amount),
+        }
+    }
+
+    #[test]
+    fn held_tokens_are_netted() {
+        let deposits = vec![event("a", 2), event("b", 1), event("a", 3), event("c", 1)];
+        let withdrawals = vec![event("a", 4), event("b", 1)];
+        // a 转入两次转出一次仍持有, b 已全部转出
+        assert_eq!(
+            held_token_page(deposits, withdrawals, 0, 10),
+            vec![token_id("a"), token_id("c")]
+        );
+    }
+
+    #[test]
+    fn held_tokens_keep_first_deposit_order() {
+        let deposits = vec![event("c", 1), event("a", 1), event("c", 1), event("b", 1)];
+        assert_eq!(
+            held_token_page(deposits, vec![], 0, 10),
+            vec![token_id("c"), token_id("a"), token_id("b")]
+        );
+    }
+
+    #[test]
+    fn held_tokens_pages() {
+        let deposits = || {
+            vec![
+                event("a", 1),
+                event("b", 1),
+                event("c", 1),
+                event("d", 1),
+                event("e", 1),
+            ]
+        };
+        // 已转出的 token 不占分页位置
+        let withdrawals = || vec![event("b", 1)];
+        assert_eq!(
+            held_token_page(deposits(), withdrawals(), 0, 2),
+            vec![token_id("a"), token_id("c")]
+        );
+        assert_eq!(
+            held_token_page(deposits(), withdrawals(), 2, 2),
+            vec![token_id("d"), token_id("e")]
+        );
+        assert!(held_token_page(deposits(), withdrawals(), 4, 2).is_empty());
+        assert!(held_token_page(deposits(), withdrawals(), 0, 0).is_empty());
+    }
+
+    #[test]
+    fn royalty_payout() {
+        assert_eq!(royalty(5, 100).payout(1_000), 50);
+        // 向下取整
+        assert_eq!(royalty(1, 3).payout(10), 3);
+        assert_eq!(royalty(1, 100).payout(99), 0);
+        assert_eq!(royalty(0, 100).payout(1_000), 0);
+    }
+
+    #[test]
+    fn royalty_payout_zero_denominator() {
+        assert_eq!(royalty(5, 0).payout(1_000), 0);
+        assert_eq!(royalty(0, 0).payout(u64::MAX), 0);
+    }
+
+    #[test]
+    fn royalty_payout_large_values() {
+        // sale_price * numerator 超出 u64
+        assert_eq!(royalty(u64::MAX, u64::MAX).payout(u64::MAX), u64::MAX);
+        assert_eq!(royalty(1, 2).payout(u64::MAX), u64::MAX / 2);
+        assert_eq!(
+            royalty(u64::MAX - 1, u64::MAX).payout(u64::MAX),
+            u64::MAX - 1
+        );
+        // numerator 大于 denominator 时不超过 sale_price
+        assert_eq!(royalty(200, 100).payout(1_000), 1_000);
+        assert_eq!(royalty(u64::MAX, 1).payout(u64::MAX), u64::MAX);
+    }
 }
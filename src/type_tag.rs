@@ -0,0 +1,173 @@
+use move_core_types::{
+    language_storage::{StructTag, TypeTag},
+    parser::parse_type_tag,
+};
+
+/// Canonical form of a Move type as used by the REST API, with short addresses
+///
+/// e.g. `0x1::coin::CoinStore<0x1::aptos_coin::AptosCoin>`
+pub fn format_type_tag(tag: &TypeTag) -> String {
+    match tag {
+        TypeTag::Bool => "bool".to_string(),
+        TypeTag::U8 => "u8".to_string(),
+        TypeTag::U16 => "u16".to_string(),
+        TypeTag::U32 => "u32".to_string(),
+        TypeTag::U64 => "u64".to_string(),
+        TypeTag::U128 => "u128".to_string(),
+        TypeTag::U256 => "u256".to_string(),
+        TypeTag::Address => "address".to_string(),
+        TypeTag::Signer => "signer".to_string(),
+        TypeTag::Vector(inner) => format!("vector<{}>", format_type_tag(inner)),
+        TypeTag::Struct(tag) => format_struct_tag(tag),
+    }
+}
+
+pub fn format_struct_tag(tag: &StructTag) -> String {
+    let name = format!(
+        "{}::{}::{}",
+        tag.address.to_hex_literal(),
+        tag.module,
+        tag.name
+    );
+    if tag.type_params.is_empty() {
+        name
+    } else {
+        let type_params: Vec<String> = tag.type_params.iter().map(format_type_tag).collect();
+        format!("{}<{}>", name, type_params.join(", "))
+    }
+}
+
+/// A Move type given as a `TypeTag`, a `StructTag` or a type string
+pub trait IntoTypeTag {
+    fn into_type_tag(self) -> Result<TypeTag, anyhow::Error>;
+}
+
+impl IntoTypeTag for TypeTag {
+    fn into_type_tag(self) -> Result<TypeTag, anyhow::Error> {
+        Ok(self)
+    }
+}
+
+impl IntoTypeTag for &TypeTag {
+    fn into_type_tag(self) -> Result<TypeTag, anyhow::Error> {
+        Ok(self.clone())
+    }
+}
+
+impl IntoTypeTag for StructTag {
+    fn into_type_tag(self) -> Result<TypeTag, anyhow::Error> {
+        Ok(TypeTag::Struct(Box::new(self)))
+    }
+}
+
+impl IntoTypeTag for &StructTag {
+    fn into_type_tag(self) -> Result<TypeTag, anyhow::Error> {
+        Ok(TypeTag::Struct(Box::new(self.clone())))
+    }
+}
+
+impl IntoTypeTag for &str {
+    fn into_type_tag(self) -> Result<TypeTag, anyhow::Error> {
+        parse_type_tag(self)
+    }
+}
+
+impl IntoTypeTag for String {
+    fn into_type_tag(self) -> Result<TypeTag, anyhow::Error> {
+        parse_type_tag(&self)
+    }
+}
+
+impl IntoTypeTag for &String {
+    fn into_type_tag(self) -> Result<TypeTag, anyhow::Error> {
+        parse_type_tag(self)
+    }
+}
+
+// 在 URL 与请求中使用的规范类型字符串
+pub(crate) fn type_string(ty: impl IntoTypeTag) -> Result<String, anyhow::Error> {
+    Ok(format_type_tag(&ty.into_type_tag()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(s: &str) -> String {
+        format_type_tag(&s.into_type_tag().unwrap())
+    }
+
+    #[test]
+    fn primitives_and_vectors() {
+        for ty in [
+            "bool",
+            "u8",
+            "u16",
+            "u32",
+            "u64",
+            "u128",
+            "u256",
+            "address",
+            "signer",
+            "vector<u8>",
+            "vector<vector<u64>>",
+        ] {
+            assert_eq!(round_trip(ty), ty);
+        }
+    }
+
+    #[test]
+    fn nested_generics() {
+        for ty in [
+            "0x1::coin::CoinStore<0x1::aptos_coin::AptosCoin>",
+            "0x1::table::Table<address, vector<0x1::string::String>>",
+            "0x1::option::Option<0x1::object::Object<0x4::token::Token>>",
+            "vector<0xcafe::pool::Pool<0x1::aptos_coin::AptosCoin, vector<u8>>>",
+        ] {
+            assert_eq!(round_trip(ty), ty);
+        }
+        assert_eq!(
+            round_trip("0x1::pair::Pair<u64,bool>"),
+            "0x1::pair::Pair<u64, bool>"
+        );
+    }
+
+    #[test]
+    fn addresses_are_shortened() {
+        assert_eq!(
+            round_trip(
+                "0x0000000000000000000000000000000000000000000000000000000000000001::aptos_coin::AptosCoin"
+            ),
+            "0x1::aptos_coin::AptosCoin"
+        );
+        assert_eq!(
+            round_trip("0x00cafe::m::S<0x0000000000000000000000000000000000000000000000000000000000000003::token::TokenId>"),
+            "0xcafe::m::S<0x3::token::TokenId>"
+        );
+    }
+
+    #[test]
+    fn tags_and_strings_agree() {
+        let tag = "0x1::coin::CoinStore<0x1::aptos_coin::AptosCoin>"
+            .into_type_tag()
+            .unwrap();
+        let struct_tag = match &tag {
+            TypeTag::Struct(struct_tag) => struct_tag.as_ref().clone(),
+            _ => panic!("expected a struct type"),
+        };
+        assert_eq!(struct_tag.clone().into_type_tag().unwrap(), tag);
+        assert_eq!((&struct_tag).into_type_tag().unwrap(), tag);
+        assert_eq!(format_type_tag(&tag).into_type_tag().unwrap(), tag);
+        assert_eq!(
+            format_struct_tag(&struct_tag),
+            "0x1::coin::CoinStore<0x1::aptos_coin::AptosCoin>"
+        );
+    }
+
+    #[test]
+    fn invalid_types() {
+        for ty in ["", "u7", "vector<u8", "0x1::coin"] {
+            assert!(ty.into_type_tag().is_err(), "{} should not parse", ty);
+        }
+    }
+}
@@ -1,53 +1,276 @@
 use aptos_crypto::{
     ed25519::{Ed25519PrivateKey, Ed25519PublicKey},
-    traits::Uniform,
+    hash::CryptoHash,
+    traits::{SigningKey, Uniform},
 };
 use aptos_types::{
     account_address::AccountAddress,
-    transaction::{authenticator::AuthenticationKey, RawTransaction, SignedTransaction},
+    transaction::{
+        authenticator::{AccountAuthenticator, AuthenticationKey},
+        RawTransaction, SignedTransaction,
+    },
 };
 use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
 use std::fmt;
 
 use aptos_types::event::EventKey;
+use move_core_types::{
+    identifier::Identifier,
+    language_storage::{StructTag, TypeTag},
+};
+
+/// A Rust type with a known Move type, e.g. the key and value of a `Table`
+pub trait MoveType {
+    fn type_tag() -> TypeTag;
+}
+
+macro_rules! impl_move_type {
+    ($($ty:ty => $tag:expr),* $(,)?) => {
+        $(
+            impl MoveType for $ty {
+                fn type_tag() -> TypeTag {
+                    $tag
+                }
+            }
+        )*
+    };
+}
+
+impl_move_type!(
+    bool => TypeTag::Bool,
+    u8 => TypeTag::U8,
+    u64 => TypeTag::U64,
+    u128 => TypeTag::U128,
+    U64 => TypeTag::U64,
+    U128 => TypeTag::U128,
+    U256 => TypeTag::U256,
+    HexBytes => TypeTag::Vector(Box::new(TypeTag::U8)),
+    AccountAddress => TypeTag::Address,
+    String => struct_type_tag(AccountAddress::ONE, "string", "String", vec![]),
+);
+
+impl<T: MoveType> MoveType for Vec<T> {
+    fn type_tag() -> TypeTag {
+        TypeTag::Vector(Box::new(T::type_tag()))
+    }
+}
+
+/// Build the `TypeTag` of a Move struct, e.g. `0x3::token::TokenId`
+pub fn struct_type_tag(
+    address: AccountAddress,
+    module: &str,
+    name: &str,
+    type_params: Vec<TypeTag>,
+) -> TypeTag {
+    TypeTag::Struct(Box::new(StructTag {
+        address,
+        module: Identifier::new(module).unwrap(),
+        name: Identifier::new(name).unwrap(),
+        type_params,
+    }))
+}
 
 /// A string encoded U64
 ///
-/// Encoded as a string to encode into JSON
-#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+/// Encoded as a string to encode into JSON, and as a plain u64 in BCS
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Hash)]
 pub struct U64(pub u64);
 
-impl From<u64> for U64 {
-    fn from(d: u64) -> Self {
-        Self(d)
+/// A string encoded U128, e.g. coin supply
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Hash)]
+pub struct U128(pub u128);
+
+/// A string encoded U256
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Hash)]
+pub struct U256(pub move_core_types::u256::U256);
+
+macro_rules! impl_string_encoded {
+    ($($ty:ident => $inner:ty),* $(,)?) => {
+        $(
+            impl From<$inner> for $ty {
+                fn from(d: $inner) -> Self {
+                    Self(d)
+                }
+            }
+
+            impl From<$ty> for $inner {
+                fn from(d: $ty) -> Self {
+                    d.0
+                }
+            }
+
+            impl fmt::Display for $ty {
+                fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    write!(f, "{}", &self.0)
+                }
+            }
+
+            impl Serialize for $ty {
+                fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    if serializer.is_human_readable() {
+                        self.0.to_string().serialize(serializer)
+                    } else {
+                        self.0.serialize(serializer)
+                    }
+                }
+            }
+
+            impl<'de> Deserialize<'de> for $ty {
+                fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+                where
+                    D: Deserializer<'de>,
+                {
+                    if deserializer.is_human_readable() {
+                        let s = <String>::deserialize(deserializer)?;
+                        Ok(Self(s.parse::<$inner>().map_err(D::Error::custom)?))
+                    } else {
+                        Ok(Self(<$inner>::deserialize(deserializer)?))
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_string_encoded!(
+    U64 => u64,
+    U128 => u128,
+    U256 => move_core_types::u256::U256,
+);
+
+/// Move `vector<u8>`
+///
+/// Encoded as a 0x prefixed hex string in JSON, and as raw bytes in BCS
+#[derive(Debug, Clone, Default, Eq, PartialEq, Hash)]
+pub struct HexBytes(pub Vec<u8>);
+
+impl From<Vec<u8>> for HexBytes {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self(bytes)
     }
 }
 
-impl From<U64> for u64 {
-    fn from(d: U64) -> Self {
-        d.0
+impl From<HexBytes> for Vec<u8> {
+    fn from(bytes: HexBytes) -> Self {
+        bytes.0
     }
 }
 
-impl fmt::Display for U64 {
+impl fmt::Display for HexBytes {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", &self.0)
+        write!(f, "0x{}", hex::encode(&self.0))
+    }
+}
+
+impl Serialize for HexBytes {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            self.to_string().serialize(serializer)
+        } else {
+            self.0.serialize(serializer)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for HexBytes {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        if deserializer.is_human_readable() {
+            let s = <String>::deserialize(deserializer)?;
+            Ok(Self(
+                hex::decode(s.trim_start_matches("0x")).map_err(D::Error::custom)?,
+            ))
+        } else {
+            Ok(Self(<Vec<u8>>::deserialize(deserializer)?))
+        }
+    }
+}
+
+/// Move `0x1::option::Option<T>`
+///
+/// Encoded as `{"vec": []}` or `{"vec": [value]}` in JSON, and as a vector of 0 or 1 element in BCS
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct MoveOption<T>(pub Option<T>);
+
+impl<T> MoveOption<T> {
+    pub fn as_option(&self) -> Option<&T> {
+        self.0.as_ref()
+    }
+
+    pub fn into_option(self) -> Option<T> {
+        self.0
+    }
+}
+
+impl<T> Default for MoveOption<T> {
+    fn default() -> Self {
+        Self(None)
     }
 }
 
-impl Serialize for U64 {
+impl<T> From<Option<T>> for MoveOption<T> {
+    fn from(value: Option<T>) -> Self {
+        Self(value)
+    }
+}
+
+impl<T> From<MoveOption<T>> for Option<T> {
+    fn from(value: MoveOption<T>) -> Self {
+        value.0
+    }
+}
+
+// JSON 与 BCS 均为 struct Option { vec: vector<T> }
+#[derive(Serialize, Deserialize)]
+struct RawOption<T> {
+    vec: Vec<T>,
+}
+
+impl<T: Serialize> Serialize for MoveOption<T> {
     fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
-        self.0.to_string().serialize(serializer)
+        RawOption {
+            vec: self.0.iter().collect(),
+        }
+        .serialize(serializer)
     }
 }
 
-impl<'de> Deserialize<'de> for U64 {
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for MoveOption<T> {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: Deserializer<'de>,
     {
-        let s = <String>::deserialize(deserializer)?;
-        Ok(Self(s.parse::<u64>().map_err(D::Error::custom)?))
+        let mut raw = RawOption::<T>::deserialize(deserializer)?;
+        if raw.vec.len() > 1 {
+            return Err(D::Error::custom("option with more than one element"));
+        }
+        Ok(Self(raw.vec.pop()))
+    }
+}
+
+/// Move `0x1::object::Object<T>`, the address of the object
+///
+/// Encoded as `{"inner": "0x..."}` in JSON, and as the address in BCS
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct ObjectRef {
+    pub inner: AccountAddress,
+}
+
+impl ObjectRef {
+    pub fn new(inner: AccountAddress) -> Self {
+        Self { inner }
+    }
+
+    pub fn address(&self) -> AccountAddress {
+        self.inner
+    }
+}
+
+impl From<AccountAddress> for ObjectRef {
+    fn from(inner: AccountAddress) -> Self {
+        Self { inner }
     }
 }
 
@@ -96,6 +319,18 @@ impl LocalAccount {
             .into_inner()
     }
 
+    /// Sign a message such as a multi-agent `RawTransactionWithData`
+    pub fn sign_message<T: CryptoHash + Serialize>(
+        &self,
+        message: &T,
+    ) -> Result<AccountAuthenticator, anyhow::Error> {
+        let signature = self.private_key().sign(message)?;
+        Ok(AccountAuthenticator::ed25519(
+            self.public_key().clone(),
+            signature,
+        ))
+    }
+
     pub fn address(&self) -> AccountAddress {
         self.address
     }
@@ -178,3 +413,120 @@ impl From<Ed25519PrivateKey> for AccountKey {
         Self::from_private_key(private_key)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn integers_as_strings_in_json() {
+        assert_eq!(
+            serde_json::to_value(U64(u64::MAX)).unwrap(),
+            json!("18446744073709551615")
+        );
+        assert_eq!(serde_json::from_value::<U64>(json!("42")).unwrap(), U64(42));
+        assert!(serde_json::from_value::<U64>(json!(42)).is_err());
+        assert!(serde_json::from_value::<U64>(json!("-1")).is_err());
+        assert_eq!(
+            serde_json::from_value::<U128>(json!("340282366920938463463374607431768211455"))
+                .unwrap(),
+            U128(u128::MAX)
+        );
+        let u256: U256 = serde_json::from_value(json!("511")).unwrap();
+        assert_eq!(serde_json::to_value(u256).unwrap(), json!("511"));
+    }
+
+    #[test]
+    fn integers_as_numbers_in_bcs() {
+        assert_eq!(
+            bcs::to_bytes(&U64(1)).unwrap(),
+            vec![1, 0, 0, 0, 0, 0, 0, 0]
+        );
+        assert_eq!(
+            bcs::from_bytes::<U64>(&[1, 0, 0, 0, 0, 0, 0, 0]).unwrap(),
+            U64(1)
+        );
+        assert_eq!(bcs::to_bytes(&U128(1)).unwrap().len(), 16);
+        let u256 = U256("511".parse().unwrap());
+        let mut bytes = vec![0u8; 32];
+        bytes[0] = 0xff;
+        bytes[1] = 0x01;
+        assert_eq!(bcs::to_bytes(&u256).unwrap(), bytes);
+        assert_eq!(bcs::from_bytes::<U256>(&bytes).unwrap(), u256);
+    }
+
+    #[test]
+    fn hex_bytes() {
+        let bytes = HexBytes(vec![1, 2]);
+        assert_eq!(serde_json::to_value(&bytes).unwrap(), json!("0x0102"));
+        assert_eq!(
+            serde_json::from_value::<HexBytes>(json!("0x0102")).unwrap(),
+            bytes
+        );
+        assert_eq!(
+            serde_json::from_value::<HexBytes>(json!("0102")).unwrap(),
+            bytes
+        );
+        assert_eq!(
+            serde_json::from_value::<HexBytes>(json!("0x")).unwrap(),
+            HexBytes::default()
+        );
+        assert!(serde_json::from_value::<HexBytes>(json!("0xzz")).is_err());
+        assert_eq!(bcs::to_bytes(&bytes).unwrap(), vec![2, 1, 2]);
+        assert_eq!(bcs::from_bytes::<HexBytes>(&[2, 1, 2]).unwrap(), bytes);
+    }
+
+    #[test]
+    fn move_option_json() {
+        assert_eq!(
+            serde_json::to_value(MoveOption::<U64>(None)).unwrap(),
+            json!({"vec": []})
+        );
+        assert_eq!(
+            serde_json::to_value(MoveOption(Some(U64(5)))).unwrap(),
+            json!({"vec": ["5"]})
+        );
+        assert_eq!(
+            serde_json::from_value::<MoveOption<U64>>(json!({"vec": []})).unwrap(),
+            MoveOption(None)
+        );
+        assert_eq!(
+            serde_json::from_value::<MoveOption<U64>>(json!({"vec": ["5"]}))
+                .unwrap()
+                .into_option(),
+            Some(U64(5))
+        );
+        assert!(serde_json::from_value::<MoveOption<U64>>(json!({"vec": ["1", "2"]})).is_err());
+    }
+
+    #[test]
+    fn move_option_bcs() {
+        assert_eq!(bcs::to_bytes(&MoveOption::<u8>(None)).unwrap(), vec![0]);
+        assert_eq!(bcs::to_bytes(&MoveOption(Some(7u8))).unwrap(), vec![1, 7]);
+        assert_eq!(
+            bcs::from_bytes::<MoveOption<u8>>(&[1, 7]).unwrap(),
+            MoveOption(Some(7))
+        );
+        assert!(bcs::from_bytes::<MoveOption<u8>>(&[2, 7, 8]).is_err());
+    }
+
+    #[test]
+    fn object_ref() {
+        let object = ObjectRef::new(AccountAddress::ONE);
+        let value = serde_json::to_value(object).unwrap();
+        assert!(value.get("inner").is_some());
+        assert_eq!(serde_json::from_value::<ObjectRef>(value).unwrap(), object);
+        assert_eq!(
+            serde_json::from_value::<ObjectRef>(json!({"inner": "0x1"})).unwrap(),
+            object
+        );
+        let mut address = vec![0u8; 32];
+        address[31] = 1;
+        assert_eq!(bcs::to_bytes(&object).unwrap(), address);
+        assert_eq!(
+            bcs::from_bytes::<ObjectRef>(&address).unwrap().address(),
+            AccountAddress::ONE
+        );
+    }
+}